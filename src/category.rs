@@ -0,0 +1,163 @@
+// file categories that decide the colour of a file name
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+
+use crate::theme::{Style, Theme};
+
+// name, parts of the file name, extensions
+// the first matching category wins, so the notes come before the extensions
+const BUILTIN: &[(&str, &[&str], &[&str])] = &[
+    ("notes-info", &["todo", "info", "log"], &[]),
+    ("notes-important", &["readme", "important"], &[]),
+    ("notes-warn", &["fixme", "warn", "warning"], &[]),
+    ("exec", &[], &["exe", "msi", "bat"]),
+    (
+        "config",
+        &[],
+        &[
+            "md", "cfg", "conf", "config", "ini", "json", "toml", "yaml", "yml", "csv", "markdown",
+            "org", "rst", "xml", "ron",
+        ],
+    ),
+    (
+        "source",
+        &[],
+        &[
+            "py", "pl", "rs", "c", "cpp", "awk", "vb", "cabal", "clj", "cs", "csx", "css", "h",
+            "hpp", "dart", "ex", "exs", "elc", "elm", "erl", "fs", "go", "hs", "ipynb", "java",
+            "bsh", "js", "jl", "kt", "tex", "lisp", "lua", "matlab", "pas", "p", "php", "ps1", "r",
+            "rb", "scala", "sh", "bash", "zsh", "fish", "sql", "swift", "ts", "tsx", "vim",
+            "cmake", "make",
+        ],
+    ),
+    (
+        "office",
+        &[],
+        &[
+            "doc", "docx", "epub", "odt", "pdf", "ps", "xls", "xlsx", "ods", "xlr", "ppt", "pptx",
+            "odp", "pps", "ics",
+        ],
+    ),
+    // backup files like 'notes.txt~' end with a tilde instead of having an extension
+    ("other", &["~"], &["git", "gitignore", "tmp", "lock"]),
+    (
+        "media",
+        &[],
+        &[
+            "bmp", "gif", "jpeg", "jpg", "png", "svg", "avi", "mp4", "wmv", "wma", "mp3", "wav",
+            "mid", "ttf", "m4a",
+        ],
+    ),
+    (
+        "archive",
+        &[],
+        &[
+            "apk", "deb", "rpm", "xbps", "bag", "bin", "dmg", "img", "iso", "toast", "vcd", "7z",
+            "arj", "gz", "zip", "pkg", "tar", "jar", "rar", "tgz", "z", "zst", "xz",
+        ],
+    ),
+];
+
+// a category in the config file either extends a bundled category
+// or defines a new one, which should then also set a style
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CategoryConfig {
+    pub names: Vec<String>,
+    pub extensions: Vec<String>,
+    // replace the bundled names and extensions instead of adding to them
+    pub replace: bool,
+    pub style: Option<Style>,
+}
+
+pub struct Category {
+    name: String,
+    names: Vec<String>,
+    extensions: Vec<String>,
+    style: Style,
+}
+
+impl Category {
+    fn matches(&self, name: &str, extension: &str) -> bool {
+        self.names.iter().any(|part| name.contains(part.as_str()))
+            || (!extension.is_empty() && self.extensions.iter().any(|ext| ext == extension))
+    }
+}
+
+// new categories from the config are checked before the bundled ones
+pub fn build(theme: &Theme, configs: &BTreeMap<String, CategoryConfig>) -> Vec<Category> {
+    let mut categories: Vec<Category> = configs
+        .iter()
+        .filter(|(name, _)| !BUILTIN.iter().any(|(builtin, _, _)| builtin == name))
+        .map(|(name, config)| Category {
+            name: name.clone(),
+            names: lowercase(&config.names),
+            extensions: lowercase(&config.extensions),
+            style: config.style.unwrap_or(theme.file),
+        })
+        .collect();
+
+    for (name, names, extensions) in BUILTIN {
+        let mut category = Category {
+            name: name.to_string(),
+            names: names.iter().map(|name| name.to_string()).collect(),
+            extensions: extensions.iter().map(|ext| ext.to_string()).collect(),
+            style: role(theme, name),
+        };
+
+        if let Some(config) = configs.get(*name) {
+            if config.replace {
+                category.names.clear();
+                category.extensions.clear();
+            }
+            category.names.extend(lowercase(&config.names));
+            category.extensions.extend(lowercase(&config.extensions));
+            if let Some(style) = config.style {
+                category.style = style;
+            }
+        }
+
+        categories.push(category);
+    }
+
+    categories
+}
+
+pub fn style<'a>(categories: &'a [Category], name: &str, extension: &str) -> Option<&'a Style> {
+    find(categories, name, extension).map(|category| &category.style)
+}
+
+pub fn name<'a>(categories: &'a [Category], name: &str, extension: &str) -> Option<&'a str> {
+    find(categories, name, extension).map(|category| category.name.as_str())
+}
+
+// the first category matching the file, matched case-insensitive
+fn find<'a>(categories: &'a [Category], name: &str, extension: &str) -> Option<&'a Category> {
+    let name = name.to_lowercase();
+    let extension = extension.to_lowercase();
+
+    categories
+        .iter()
+        .find(|category| category.matches(&name, &extension))
+}
+
+fn role(theme: &Theme, name: &str) -> Style {
+    match name {
+        "notes-info" => theme.notes_info,
+        "notes-important" => theme.notes_important,
+        "notes-warn" => theme.notes_warn,
+        "exec" => theme.exec,
+        "config" => theme.config,
+        "source" => theme.source,
+        "office" => theme.office,
+        "other" => theme.other,
+        "media" => theme.media,
+        "archive" => theme.archive,
+        _ => theme.file,
+    }
+}
+
+fn lowercase(values: &[String]) -> Vec<String> {
+    values.iter().map(|value| value.to_lowercase()).collect()
+}
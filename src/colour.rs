@@ -0,0 +1,189 @@
+// when to colour the output and how many colours the terminal supports
+use owo_colors::{AnsiColors, XtermColors};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use std::{
+    env,
+    io::{self, IsTerminal},
+};
+
+use crate::theme::Rgb;
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColourMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColourMode {
+    pub fn from_arg(arg: &str) -> ColourMode {
+        match arg {
+            "always" => ColourMode::Always,
+            "never" => ColourMode::Never,
+            _ => ColourMode::Auto,
+        }
+    }
+
+    // auto colours only when writing to a terminal,
+    // NO_COLOR turns colours off and CLICOLOR_FORCE turns them on for pipes
+    pub fn enabled(self) -> bool {
+        match self {
+            ColourMode::Always => true,
+            ColourMode::Never => false,
+            ColourMode::Auto => {
+                if env_set("NO_COLOR") {
+                    false
+                } else if env::var("CLICOLOR_FORCE").is_ok_and(|value| value != "0") {
+                    true
+                } else {
+                    io::stdout().is_terminal()
+                }
+            }
+        }
+    }
+}
+
+// older config files used 'colour = true' and 'colour = false'
+impl<'de> Deserialize<'de> for ColourMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Value {
+            Flag(bool),
+            Mode(String),
+        }
+
+        match Value::deserialize(deserializer)? {
+            Value::Flag(true) => Ok(ColourMode::Always),
+            Value::Flag(false) => Ok(ColourMode::Never),
+            Value::Mode(mode) => match mode.as_str() {
+                "auto" | "always" | "never" => Ok(ColourMode::from_arg(&mode)),
+                _ => Err(serde::de::Error::custom(format!(
+                    "invalid colour mode \'{mode}\', expected auto, always or never"
+                ))),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Depth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+// truecolor if COLORTERM says so, otherwise guessed from TERM
+pub fn depth() -> Depth {
+    if env::var("COLORTERM").is_ok_and(|value| value == "truecolor" || value == "24bit") {
+        return Depth::TrueColor;
+    }
+
+    match env::var("TERM") {
+        Ok(term) if term.contains("256") => Depth::Ansi256,
+        Ok(_) => Depth::Ansi16,
+        // the windows console has no TERM but supports truecolor since windows 10
+        Err(_) if cfg!(windows) => Depth::TrueColor,
+        Err(_) => Depth::Ansi16,
+    }
+}
+
+fn env_set(name: &str) -> bool {
+    env::var_os(name).is_some_and(|value| !value.is_empty())
+}
+
+// nearest colour of the 6x6x6 cube or the grayscale ramp
+pub fn to_ansi256(Rgb(r, g, b): Rgb) -> XtermColors {
+    let cube = |c: u8| match c {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (c - 35) / 40,
+    };
+    let level = |i: u8| if i == 0 { 0 } else { 55 + i * 40 };
+    let (cr, cg, cb) = (cube(r), cube(g), cube(b));
+    let cube_colour = Rgb(level(cr), level(cg), level(cb));
+
+    let average = (r as u16 + g as u16 + b as u16) / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + gray_index * 10;
+    let gray_colour = Rgb(gray, gray, gray);
+
+    let index = match distance(Rgb(r, g, b), gray_colour) < distance(Rgb(r, g, b), cube_colour) {
+        true => 232 + gray_index,
+        false => 16 + 36 * cr + 6 * cg + cb,
+    };
+
+    XtermColors::from(index)
+}
+
+// keeps the hue instead of the nearest colour by distance,
+// which turns the pastel colours of most themes into gray
+pub fn to_ansi16(Rgb(r, g, b): Rgb) -> AnsiColors {
+    let max = r.max(g).max(b) as i32;
+    let min = r.min(g).min(b) as i32;
+    let chroma = max - min;
+    let lightness = (max + min) / 2;
+
+    // colours with a saturation below 20% are shades of gray
+    if chroma * 5 <= 255 - (2 * lightness - 255).abs() {
+        return match lightness {
+            0..64 => AnsiColors::Black,
+            64..160 => AnsiColors::BrightBlack,
+            160..224 => AnsiColors::White,
+            _ => AnsiColors::BrightWhite,
+        };
+    }
+
+    let (r, g, b) = (r as i32, g as i32, b as i32);
+    let hue = if max == r {
+        (60 * (g - b) / chroma).rem_euclid(360)
+    } else if max == g {
+        60 * (b - r) / chroma + 120
+    } else {
+        60 * (r - g) / chroma + 240
+    };
+
+    let bright = lightness >= 150;
+    let pick = |normal: AnsiColors, light: AnsiColors| match bright {
+        true => light,
+        false => normal,
+    };
+    match hue {
+        0..30 | 330.. => pick(AnsiColors::Red, AnsiColors::BrightRed),
+        30..90 => pick(AnsiColors::Yellow, AnsiColors::BrightYellow),
+        90..150 => pick(AnsiColors::Green, AnsiColors::BrightGreen),
+        150..210 => pick(AnsiColors::Cyan, AnsiColors::BrightCyan),
+        210..270 => pick(AnsiColors::Blue, AnsiColors::BrightBlue),
+        _ => pick(AnsiColors::Magenta, AnsiColors::BrightMagenta),
+    }
+}
+
+fn distance(Rgb(r1, g1, b1): Rgb, Rgb(r2, g2, b2): Rgb) -> u32 {
+    let diff = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    diff(r1, r2) + diff(g1, g2) + diff(b1, b2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ansi16_keeps_the_hue() {
+        assert_eq!(to_ansi16(Rgb(226, 120, 120)), AnsiColors::BrightRed);
+        assert_eq!(to_ansi16(Rgb(139, 0, 0)), AnsiColors::Red);
+        assert_eq!(to_ansi16(Rgb(109, 144, 217)), AnsiColors::BrightBlue);
+        assert_eq!(to_ansi16(Rgb(137, 184, 194)), AnsiColors::BrightCyan);
+        assert_eq!(to_ansi16(Rgb(102, 142, 61)), AnsiColors::Green);
+        assert_eq!(to_ansi16(Rgb(119, 89, 180)), AnsiColors::Blue);
+    }
+
+    #[test]
+    fn ansi16_grays() {
+        assert_eq!(to_ansi16(Rgb(0, 0, 0)), AnsiColors::Black);
+        assert_eq!(to_ansi16(Rgb(107, 112, 137)), AnsiColors::BrightBlack);
+        assert_eq!(to_ansi16(Rgb(198, 200, 209)), AnsiColors::White);
+        assert_eq!(to_ansi16(Rgb(255, 255, 255)), AnsiColors::BrightWhite);
+    }
+}
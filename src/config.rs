@@ -0,0 +1,130 @@
+// default flags loaded from <config_dir>/sl/sl.toml
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    category::CategoryConfig,
+    colour::ColourMode,
+    export,
+    icons::{IconConfig, IconMode},
+    size::SizeStyle,
+    sort::GroupDirs,
+    sort::SortBy,
+    theme,
+    time::{TimeField, TimeStyle},
+};
+
+const CONFIG_FILE: &str = "sl.toml";
+
+// every value is optional, flags given on the command line take precedence
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub long: Option<bool>,
+    pub colour: Option<ColourMode>,
+    pub hidden: Option<bool>,
+    pub fullpath: Option<bool>,
+    // 0 is rejected, like --level 0
+    pub level: Option<NonZeroUsize>,
+    pub sort: Option<SortBy>,
+    pub reverse: Option<bool>,
+    pub group_dirs: Option<GroupDirs>,
+    pub format: Option<export::Format>,
+    pub oneline: Option<bool>,
+    pub across: Option<bool>,
+    pub dereference: Option<bool>,
+    pub theme: Option<String>,
+    pub ls_colors: Option<bool>,
+    // extends the bundled file categories or adds new ones
+    pub categories: Option<BTreeMap<String, CategoryConfig>>,
+    pub icons: Option<IconMode>,
+    // replaces or adds icons for file names, extensions, categories and types
+    pub icon_map: Option<IconConfig>,
+    pub git: Option<bool>,
+    pub git_ignore: Option<bool>,
+    pub ignore_glob: Option<Vec<String>>,
+    pub octal: Option<bool>,
+    pub system: Option<bool>,
+    pub time: Option<TimeField>,
+    pub time_style: Option<TimeStyle>,
+    // short, si, binary, bytes or a block size like 'M'
+    pub size_style: Option<SizeStyle>,
+}
+
+impl Config {
+    // the configuration with defaults filled in for unset values
+    pub fn effective(&self) -> Config {
+        Config {
+            long: Some(self.long.unwrap_or(false)),
+            colour: Some(self.colour.unwrap_or(ColourMode::Auto)),
+            hidden: Some(self.hidden.unwrap_or(false)),
+            fullpath: Some(self.fullpath.unwrap_or(false)),
+            level: self.level,
+            sort: Some(self.sort.unwrap_or(SortBy::Name)),
+            reverse: Some(self.reverse.unwrap_or(false)),
+            group_dirs: Some(self.group_dirs.unwrap_or(GroupDirs::None)),
+            format: self.format,
+            oneline: Some(self.oneline.unwrap_or(false)),
+            across: Some(self.across.unwrap_or(false)),
+            dereference: Some(self.dereference.unwrap_or(false)),
+            theme: Some(
+                self.theme
+                    .clone()
+                    .unwrap_or(theme::DEFAULT_THEME.to_string()),
+            ),
+            ls_colors: Some(self.ls_colors.unwrap_or(true)),
+            categories: self.categories.clone(),
+            icons: Some(self.icons.unwrap_or(IconMode::Never)),
+            icon_map: self.icon_map.clone(),
+            git: Some(self.git.unwrap_or(false)),
+            git_ignore: Some(self.git_ignore.unwrap_or(false)),
+            ignore_glob: Some(self.ignore_glob.clone().unwrap_or_default()),
+            octal: Some(self.octal.unwrap_or(false)),
+            system: Some(self.system.unwrap_or(false)),
+            time: Some(self.time.unwrap_or(TimeField::Modified)),
+            time_style: Some(self.time_style.clone().unwrap_or(TimeStyle::Relative)),
+            size_style: Some(self.size_style.clone().unwrap_or(SizeStyle::Short)),
+        }
+    }
+}
+
+pub fn config_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(CONFIG_FILE)
+}
+
+// a missing config file is fine, an invalid one is reported and ignored
+pub fn load(config_dir: &Path) -> Config {
+    let path = config_path(config_dir);
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Config::default(),
+        Err(err) => {
+            warn!(
+                "Unable to read the config file \'{}\': {}",
+                path.display(),
+                err
+            );
+            return Config::default();
+        }
+    };
+
+    toml::from_str(&content).unwrap_or_else(|err| {
+        warn!("Invalid config file \'{}\': {}", path.display(), err);
+        Config::default()
+    })
+}
+
+pub fn show(config: &Config) -> String {
+    toml::to_string(&config.effective()).unwrap_or_else(|err| {
+        warn!("Unable to show the configuration: {err}");
+        String::new()
+    })
+}
@@ -0,0 +1,165 @@
+// machine-readable output of directory entries
+use chrono::{DateTime, Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+
+use std::{
+    io::{self, Write},
+    time::SystemTime,
+};
+
+use crate::{
+    filetype_name, format_permissions,
+    platform::{self, Owners},
+    FileData,
+};
+
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    // one json array containing every entry
+    Json,
+    // one json object per line
+    Ndjson,
+    Csv,
+    Tsv,
+}
+
+impl Format {
+    pub fn from_arg(arg: &str) -> Format {
+        match arg {
+            "ndjson" => Format::Ndjson,
+            "csv" => Format::Csv,
+            "tsv" => Format::Tsv,
+            _ => Format::Json,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    name: &'a str,
+    path: &'a str,
+    #[serde(rename = "type")]
+    filetype: &'static str,
+    size: u64,
+    modified: Option<String>,
+    modified_epoch: Option<i64>,
+    accessed: Option<String>,
+    created: Option<String>,
+    changed: Option<String>,
+    permissions: String,
+    // octal mode, owner, group and link count are only known on unix
+    mode: Option<String>,
+    owner: Option<String>,
+    group: Option<String>,
+    links: Option<u64>,
+    // the windows attributes like 'R-SA----'
+    attributes: Option<String>,
+    readonly: Option<bool>,
+    hidden: bool,
+    extension: Option<&'a str>,
+    symlink_target: Option<String>,
+}
+
+impl<'a> Record<'a> {
+    fn new(entry: &'a FileData, owners: &Owners) -> Record<'a> {
+        let modified: Option<DateTime<Local>> = entry.modified.map(DateTime::from);
+
+        Record {
+            name: &entry.name,
+            path: &entry.path,
+            filetype: filetype_name(entry.filetype),
+            size: entry.filesize,
+            modified: modified.map(|modified| modified.to_rfc3339_opts(SecondsFormat::Secs, false)),
+            modified_epoch: modified.map(|modified| modified.timestamp()),
+            accessed: rfc3339(entry.accessed),
+            created: rfc3339(entry.created),
+            changed: rfc3339(entry.changed),
+            permissions: format_permissions(entry, false),
+            mode: entry.unix.map(|unix| format!("{:04o}", unix.mode & 0o7777)),
+            owner: entry.unix.map(|unix| owners.user_name(unix.uid)),
+            group: entry.unix.map(|unix| owners.group_name(unix.gid)),
+            links: entry.unix.map(|unix| unix.nlink),
+            attributes: entry.attributes.map(platform::format_attributes),
+            readonly: entry
+                .permissions
+                .as_ref()
+                .map(|permissions| permissions.readonly()),
+            hidden: entry.hidden,
+            extension: match entry.file_extension.is_empty() {
+                true => None,
+                false => Some(&entry.file_extension),
+            },
+            symlink_target: entry
+                .symlink_target
+                .as_ref()
+                .map(|target| target.to_string_lossy().to_string()),
+        }
+    }
+}
+
+fn rfc3339(time: Option<SystemTime>) -> Option<String> {
+    time.map(|time| DateTime::<Local>::from(time).to_rfc3339_opts(SecondsFormat::Secs, false))
+}
+
+pub fn write_entries(entries: &[FileData], format: Format, owners: &Owners) -> io::Result<()> {
+    let records: Vec<Record> = entries
+        .iter()
+        .map(|entry| Record::new(entry, owners))
+        .collect();
+
+    let mut stdout = io::stdout().lock();
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut stdout, &records)?;
+            writeln!(stdout)?;
+        }
+        Format::Ndjson => {
+            for record in records {
+                serde_json::to_writer(&mut stdout, &record)?;
+                writeln!(stdout)?;
+            }
+        }
+        Format::Csv => write_table(&mut stdout, &records, b',')?,
+        Format::Tsv => write_table(&mut stdout, &records, b'\t')?,
+    }
+
+    stdout.flush()
+}
+
+// writes the columns of the long listing, quoting fields that contain delimiters or newlines
+fn write_table(writer: &mut impl Write, records: &[Record], delimiter: u8) -> io::Result<()> {
+    let mut table = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(writer);
+
+    table.write_record([
+        "type",
+        "permissions",
+        "links",
+        "owner",
+        "group",
+        "size",
+        "modified",
+        "name",
+        "path",
+    ])?;
+    for record in records {
+        table.write_record([
+            record.filetype,
+            &record.permissions,
+            &record
+                .links
+                .map(|links| links.to_string())
+                .unwrap_or_default(),
+            record.owner.as_deref().unwrap_or_default(),
+            record.group.as_deref().unwrap_or_default(),
+            &record.size.to_string(),
+            record.modified.as_deref().unwrap_or_default(),
+            record.name,
+            record.path,
+        ])?;
+    }
+
+    table.flush()
+}
@@ -0,0 +1,263 @@
+// include and exclude filters on the names of the entries
+use glob::Pattern;
+use regex::Regex;
+
+use std::time::{Duration, SystemTime};
+
+use crate::{
+    platform,
+    size::{GB, KB, MB, TB},
+    FileData,
+};
+
+pub struct NameFilter {
+    ignore: Vec<Pattern>,
+    only: Vec<Pattern>,
+    regex: Option<Regex>,
+}
+
+impl NameFilter {
+    // every argument can hold several globs separated by '|', like 'target|*.tmp'
+    pub fn new(
+        ignore: &[String],
+        only: &[String],
+        regex: Option<&str>,
+    ) -> Result<NameFilter, String> {
+        let regex = match regex {
+            Some(regex) => {
+                Some(Regex::new(regex).map_err(|err| format!("Invalid regex \'{regex}\': {err}"))?)
+            }
+            None => None,
+        };
+
+        Ok(NameFilter {
+            ignore: patterns(ignore)?,
+            only: patterns(only)?,
+            regex,
+        })
+    }
+
+    pub fn is_excluded(&self, name: &str) -> bool {
+        self.ignore.iter().any(|pattern| pattern.matches(name))
+    }
+
+    // names have to match one of the globs and the regex, if given
+    pub fn is_included(&self, name: &str) -> bool {
+        let glob = self.only.is_empty() || self.only.iter().any(|pattern| pattern.matches(name));
+        let regex = self.regex.as_ref().is_none_or(|regex| regex.is_match(name));
+
+        glob && regex
+    }
+
+    pub fn has_includes(&self) -> bool {
+        !self.only.is_empty() || self.regex.is_some()
+    }
+}
+
+fn patterns(args: &[String]) -> Result<Vec<Pattern>, String> {
+    args.iter()
+        .flat_map(|arg| arg.split('|'))
+        .filter(|glob| !glob.is_empty())
+        .map(|glob| Pattern::new(glob).map_err(|err| format!("Invalid glob \'{glob}\': {err}")))
+        .collect()
+}
+
+// filters on the metadata, entries without metadata never match
+pub struct MetaFilter {
+    pub larger_than: Option<u64>,
+    pub smaller_than: Option<u64>,
+    pub newer_than: Option<SystemTime>,
+    pub older_than: Option<SystemTime>,
+    // lowercase extensions without the leading dot
+    pub extensions: Vec<String>,
+    // 'f' files, 'd' directories, 'l' symlinks, 'x' executables
+    pub types: Vec<char>,
+}
+
+impl MetaFilter {
+    pub fn is_active(&self) -> bool {
+        self.larger_than.is_some()
+            || self.smaller_than.is_some()
+            || self.newer_than.is_some()
+            || self.older_than.is_some()
+            || !self.extensions.is_empty()
+            || !self.types.is_empty()
+    }
+
+    pub fn is_included(&self, entry: &FileData) -> bool {
+        // sizes and extensions only make sense for files
+        let size_filter = self.larger_than.is_some() || self.smaller_than.is_some();
+        if (size_filter || !self.extensions.is_empty()) && (!entry.is_file() || entry.unreadable) {
+            return false;
+        }
+        if self.larger_than.is_some_and(|size| entry.filesize <= size) {
+            return false;
+        }
+        if self.smaller_than.is_some_and(|size| entry.filesize >= size) {
+            return false;
+        }
+
+        if self.newer_than.is_some() || self.older_than.is_some() {
+            let Some(modified) = entry.modified else {
+                return false;
+            };
+            if self.newer_than.is_some_and(|time| modified <= time) {
+                return false;
+            }
+            if self.older_than.is_some_and(|time| modified >= time) {
+                return false;
+            }
+        }
+
+        if !self.extensions.is_empty()
+            && !self
+                .extensions
+                .contains(&entry.file_extension.to_lowercase())
+        {
+            return false;
+        }
+
+        self.types.is_empty() || self.types.iter().any(|t| has_type(entry, *t))
+    }
+}
+
+fn has_type(entry: &FileData, filetype: char) -> bool {
+    match filetype {
+        'f' => entry.is_file(),
+        'd' => entry.is_dir(),
+        'l' => entry.is_symlink(),
+        'x' => {
+            entry.is_file()
+                && (entry
+                    .permissions
+                    .as_ref()
+                    .is_some_and(platform::is_executable)
+                    || platform::has_executable_extension(&entry.file_extension))
+        }
+        _ => false,
+    }
+}
+
+// sizes like '500', '10K', '1.5M' or '2GB', with the same 1024-based units as the long listing
+pub fn parse_size(arg: &str) -> Result<u64, String> {
+    let arg = arg.trim();
+    let split = arg
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(arg.len());
+    let (number, unit) = arg.split_at(split);
+
+    let invalid = || format!("invalid size \'{arg}\', expected a value like 10K or 1.5M");
+    if !number.chars().any(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+
+    // a single 'B' is optional, like in '10KB' or '500B'
+    let unit = unit.to_uppercase();
+    let unit = match unit.strip_suffix('B').unwrap_or(&unit) {
+        "" => 1,
+        "K" => KB,
+        "M" => MB,
+        "G" => GB,
+        "T" => TB,
+        _ => {
+            return Err(format!(
+                "invalid size unit in \'{arg}\', expected B, K, M, G or T"
+            ))
+        }
+    };
+
+    Ok((number * unit as f64) as u64)
+}
+
+// ages like '30s', '10min', '2h', '2d', '3w', '6mo' or '1y'
+pub fn parse_age(arg: &str) -> Result<Duration, String> {
+    let arg = arg.trim();
+    let split = arg.find(|c: char| !c.is_ascii_digit()).unwrap_or(arg.len());
+    let (number, unit) = arg.split_at(split);
+
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid age \'{arg}\', expected a value like 2d or 3h"))?;
+    let secs = match unit {
+        "s" => 1,
+        "m" | "min" => 60,
+        "h" => 60 * 60,
+        "" | "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        "mo" => 30 * 24 * 60 * 60,
+        "y" => 365 * 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "invalid age unit in \'{arg}\', expected s, min, h, d, w, mo or y"
+            ))
+        }
+    };
+
+    Ok(Duration::from_secs(number.saturating_mul(secs)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("500"), Ok(500));
+        assert_eq!(parse_size("10K"), Ok(10 * KB));
+        assert_eq!(parse_size("10kb"), Ok(10 * KB));
+        assert_eq!(parse_size("1.5M"), Ok(MB + MB / 2));
+        assert_eq!(parse_size("2GB"), Ok(2 * GB));
+        assert_eq!(parse_size(" 1T "), Ok(TB));
+        assert_eq!(parse_size("007B"), Ok(7));
+        assert_eq!(parse_size("3b"), Ok(3));
+    }
+
+    #[test]
+    fn invalid_sizes() {
+        assert!(parse_size("").is_err());
+        assert!(parse_size("M").is_err());
+        assert!(parse_size("10X").is_err());
+        assert!(parse_size("1.2.3K").is_err());
+        assert!(parse_size("-5K").is_err());
+        assert!(parse_size("10KBBB").is_err());
+        assert!(parse_size("10BB").is_err());
+        assert!(parse_size("BB").is_err());
+        assert!(parse_size("B").is_err());
+        assert!(parse_size(".K").is_err());
+    }
+
+    #[test]
+    fn ages() {
+        assert_eq!(parse_age("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_age("2"), Ok(Duration::from_secs(2 * 24 * 60 * 60)));
+        assert_eq!(
+            parse_age("3w"),
+            Ok(Duration::from_secs(3 * 7 * 24 * 60 * 60))
+        );
+        assert_eq!(parse_age("1y"), Ok(Duration::from_secs(365 * 24 * 60 * 60)));
+    }
+
+    #[test]
+    fn minutes_and_months() {
+        assert_eq!(parse_age("5m"), Ok(Duration::from_secs(5 * 60)));
+        assert_eq!(parse_age("5min"), Ok(Duration::from_secs(5 * 60)));
+        assert_eq!(
+            parse_age("5mo"),
+            Ok(Duration::from_secs(5 * 30 * 24 * 60 * 60))
+        );
+    }
+
+    #[test]
+    fn invalid_ages() {
+        assert!(parse_age("").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("5x").is_err());
+        assert!(parse_age("1.5h").is_err());
+        // saturates instead of overflowing
+        assert_eq!(
+            parse_age("18446744073709551615y"),
+            Ok(Duration::from_secs(u64::MAX))
+        );
+    }
+}
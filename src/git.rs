@@ -0,0 +1,475 @@
+// git status of the entries, read from the repository without spawning git
+use git2::{Repository, Status, StatusOptions};
+use log::warn;
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    colour::Depth,
+    theme::{Style, Theme},
+};
+
+// the status of an entry like in 'git status --short':
+// the first column is the index, the second the working tree
+#[derive(Clone, Copy, PartialEq)]
+pub struct GitStatus {
+    pub staged: char,
+    pub unstaged: char,
+}
+
+// used when a directory aggregates the status of its content
+const PRIORITY: [char; 9] = ['U', 'M', 'A', 'D', 'R', 'T', '?', '!', '-'];
+
+impl GitStatus {
+    const CLEAN: GitStatus = GitStatus {
+        staged: '-',
+        unstaged: '-',
+    };
+
+    fn from_status(status: Status) -> GitStatus {
+        if status.is_conflicted() {
+            return GitStatus {
+                staged: 'U',
+                unstaged: 'U',
+            };
+        }
+        if status.is_wt_new() {
+            return GitStatus {
+                staged: '?',
+                unstaged: '?',
+            };
+        }
+        if status.is_ignored() {
+            return GitStatus {
+                staged: '!',
+                unstaged: '!',
+            };
+        }
+
+        let staged = if status.is_index_new() {
+            'A'
+        } else if status.is_index_modified() {
+            'M'
+        } else if status.is_index_deleted() {
+            'D'
+        } else if status.is_index_renamed() {
+            'R'
+        } else if status.is_index_typechange() {
+            'T'
+        } else {
+            '-'
+        };
+
+        let unstaged = if status.is_wt_modified() {
+            'M'
+        } else if status.is_wt_deleted() {
+            'D'
+        } else if status.is_wt_renamed() {
+            'R'
+        } else if status.is_wt_typechange() {
+            'T'
+        } else {
+            '-'
+        };
+
+        GitStatus { staged, unstaged }
+    }
+
+    fn merge(self, other: GitStatus) -> GitStatus {
+        let pick = |a: char, b: char| {
+            let rank = |c: char| {
+                PRIORITY
+                    .iter()
+                    .position(|p| *p == c)
+                    .unwrap_or(PRIORITY.len())
+            };
+            match rank(a) <= rank(b) {
+                true => a,
+                false => b,
+            }
+        };
+
+        GitStatus {
+            staged: pick(self.staged, other.staged),
+            unstaged: pick(self.unstaged, other.unstaged),
+        }
+    }
+
+    pub fn paint(&self, theme: &Theme, colour: bool, depth: Depth) -> String {
+        let paint = |c: char| {
+            let text = c.to_string();
+            match colour {
+                true => style(c, theme).paint(&text, depth),
+                false => text,
+            }
+        };
+
+        format!("{}{}", paint(self.staged), paint(self.unstaged))
+    }
+}
+
+fn style(c: char, theme: &Theme) -> &Style {
+    match c {
+        'A' => &theme.git_added,
+        'M' | 'R' | 'T' => &theme.git_modified,
+        'D' => &theme.git_deleted,
+        '?' => &theme.git_untracked,
+        '!' => &theme.git_ignored,
+        'U' => &theme.git_conflicted,
+        _ => &theme.git_ignored,
+    }
+}
+
+struct Repo {
+    workdir: PathBuf,
+    // paths relative to the working tree with '/' separators,
+    // untracked and ignored directories end with '/' and are not descended into
+    statuses: HashMap<String, GitStatus>,
+    // the combined status of the content of every directory with changes
+    dirs: HashMap<String, GitStatus>,
+}
+
+impl Repo {
+    fn open(repo: Repository, workdir: PathBuf) -> Option<Repo> {
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(false)
+            .include_ignored(true)
+            .recurse_ignored_dirs(false);
+
+        let statuses = match repo.statuses(Some(&mut options)) {
+            Ok(statuses) => statuses,
+            Err(err) => {
+                warn!(
+                    "Unable to read the git status of \'{}\': {}",
+                    workdir.display(),
+                    err
+                );
+                return None;
+            }
+        };
+
+        let statuses: HashMap<String, GitStatus> = statuses
+            .iter()
+            .filter_map(|entry| {
+                let path = entry.path()?.to_string();
+                // libgit2 reports empty directories as ignored
+                if entry.status().is_ignored()
+                    && path.ends_with('/')
+                    && !repo.is_path_ignored(&path).unwrap_or(false)
+                {
+                    return None;
+                }
+                Some((path, GitStatus::from_status(entry.status())))
+            })
+            .collect();
+
+        // every change marks all of its parent directories, ignored files don't
+        let mut dirs: HashMap<String, GitStatus> = HashMap::new();
+        for (path, status) in &statuses {
+            if status.unstaged == '!' {
+                continue;
+            }
+            let path = path.trim_end_matches('/');
+            for (i, _) in path.match_indices('/') {
+                dirs.entry(path[..i].to_string())
+                    .and_modify(|dir| *dir = dir.merge(*status))
+                    .or_insert(*status);
+            }
+        }
+
+        Some(Repo {
+            workdir,
+            statuses,
+            dirs,
+        })
+    }
+
+    fn status(&self, relative: &str) -> GitStatus {
+        // the entry itself
+        let own = self
+            .statuses
+            .get(relative)
+            .or_else(|| self.statuses.get(&format!("{relative}/")));
+        if let Some(status) = own {
+            return *status;
+        }
+
+        // inside an untracked or ignored directory
+        for (i, _) in relative.match_indices('/') {
+            if let Some(status) = self.statuses.get(&relative[..=i]) {
+                return *status;
+            }
+        }
+
+        self.dirs.get(relative).copied().unwrap_or(GitStatus::CLEAN)
+    }
+}
+
+// repositories are opened once and shared by all entries of their working tree
+#[derive(Default)]
+pub struct Git {
+    repos: RefCell<Vec<Repo>>,
+    // directories that were already searched for a repository
+    searched: RefCell<HashSet<PathBuf>>,
+}
+
+impl Git {
+    // None if the entry is not inside a git working tree
+    pub fn status(&self, entry_path: &Path) -> Option<GitStatus> {
+        let path = absolute(entry_path)?;
+        let parent = path.parent()?.to_path_buf();
+
+        if !self.searched.borrow().contains(&parent) {
+            self.discover(&parent);
+            self.searched.borrow_mut().insert(parent);
+        }
+
+        let index = self.find(&path)?;
+        let repos = self.repos.borrow();
+        let repo = &repos[index];
+
+        let relative = path.strip_prefix(&repo.workdir).ok()?;
+        let relative = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        // the working tree itself and the .git directory have no status
+        if relative.is_empty() || relative == ".git" || relative.starts_with(".git/") {
+            return None;
+        }
+
+        Some(repo.status(&relative))
+    }
+
+    // the status is only read once per working tree, even for nested repositories
+    fn discover(&self, dir: &Path) {
+        let Ok(repo) = Repository::discover(dir) else {
+            return;
+        };
+        let Some(workdir) = repo
+            .workdir()
+            .and_then(|workdir| fs::canonicalize(workdir).ok())
+        else {
+            return;
+        };
+
+        let loaded = self
+            .repos
+            .borrow()
+            .iter()
+            .any(|repo| repo.workdir == workdir);
+        if !loaded {
+            if let Some(repo) = Repo::open(repo, workdir) {
+                self.repos.borrow_mut().push(repo);
+            }
+        }
+    }
+
+    // the innermost repository containing the path
+    fn find(&self, path: &Path) -> Option<usize> {
+        self.repos
+            .borrow()
+            .iter()
+            .enumerate()
+            .filter(|(_, repo)| path.starts_with(&repo.workdir))
+            .max_by_key(|(_, repo)| repo.workdir.components().count())
+            .map(|(index, _)| index)
+    }
+}
+
+// the entry itself is not resolved, so symlinks keep their own status
+pub fn absolute(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    Some(fs::canonicalize(parent).ok()?.join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{IndexEntry, IndexTime, Oid, Signature};
+
+    // a fresh repository in the temp dir, removed again when dropped
+    struct Fixture {
+        dir: PathBuf,
+        repo: Repository,
+    }
+
+    impl Fixture {
+        fn new(name: &str) -> Fixture {
+            let dir = std::env::temp_dir().join(format!("sl-git-{}-{}", std::process::id(), name));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            let repo = Repository::init(&dir).unwrap();
+            Fixture { dir, repo }
+        }
+
+        fn write(&self, path: &str, content: &str) {
+            let path = self.dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        fn stage(&self, path: &str) {
+            let mut index = self.repo.index().unwrap();
+            index.add_path(Path::new(path)).unwrap();
+            index.write().unwrap();
+        }
+
+        fn commit(&self) {
+            let mut index = self.repo.index().unwrap();
+            let tree = self.repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = Signature::now("sl", "sl@example.com").unwrap();
+            let parent = self
+                .repo
+                .head()
+                .ok()
+                .and_then(|head| head.peel_to_commit().ok());
+            let parents: Vec<_> = parent.iter().collect();
+            self.repo
+                .commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    "commit",
+                    &tree,
+                    &parents,
+                )
+                .unwrap();
+        }
+
+        fn status(&self, path: &str) -> Option<String> {
+            Git::default()
+                .status(&self.dir.join(path))
+                .map(|status| format!("{}{}", status.staged, status.unstaged))
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn conflict_entry(path: &str, stage: u16, id: Oid) -> IndexEntry {
+        IndexEntry {
+            ctime: IndexTime::new(0, 0),
+            mtime: IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode: 0o100644,
+            uid: 0,
+            gid: 0,
+            file_size: 0,
+            id,
+            flags: (stage << 12) | path.len() as u16,
+            flags_extended: 0,
+            path: path.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn file_status() {
+        let fixture = Fixture::new("file");
+        fixture.write("clean.txt", "clean");
+        fixture.write("modified.txt", "old");
+        fixture.stage("clean.txt");
+        fixture.stage("modified.txt");
+        fixture.commit();
+
+        fixture.write("modified.txt", "new");
+        fixture.write("staged.txt", "staged");
+        fixture.stage("staged.txt");
+        fixture.write("both.txt", "old");
+        fixture.stage("both.txt");
+        fixture.write("both.txt", "new");
+        fixture.write("untracked.txt", "untracked");
+        fixture.write(".gitignore", "*.log\n");
+        fixture.write("debug.log", "ignored");
+
+        assert_eq!(fixture.status("clean.txt").as_deref(), Some("--"));
+        assert_eq!(fixture.status("modified.txt").as_deref(), Some("-M"));
+        assert_eq!(fixture.status("staged.txt").as_deref(), Some("A-"));
+        assert_eq!(fixture.status("both.txt").as_deref(), Some("AM"));
+        assert_eq!(fixture.status("untracked.txt").as_deref(), Some("??"));
+        assert_eq!(fixture.status("debug.log").as_deref(), Some("!!"));
+    }
+
+    #[test]
+    fn conflicted() {
+        let fixture = Fixture::new("conflict");
+        fixture.write("base.txt", "base");
+        fixture.stage("base.txt");
+        fixture.commit();
+
+        let ours = fixture.repo.blob(b"ours").unwrap();
+        let theirs = fixture.repo.blob(b"theirs").unwrap();
+        let mut index = fixture.repo.index().unwrap();
+        index.add(&conflict_entry("merge.txt", 2, ours)).unwrap();
+        index.add(&conflict_entry("merge.txt", 3, theirs)).unwrap();
+        index.write().unwrap();
+        fixture.write("merge.txt", "<<<<<<<");
+
+        assert_eq!(fixture.status("merge.txt").as_deref(), Some("UU"));
+    }
+
+    #[test]
+    fn directory_status() {
+        let fixture = Fixture::new("dir");
+        fixture.write("src/main.rs", "old");
+        fixture.write("src/deep/lib.rs", "old");
+        fixture.write("docs/readme.md", "clean");
+        fixture.stage("src/main.rs");
+        fixture.stage("src/deep/lib.rs");
+        fixture.stage("docs/readme.md");
+        fixture.commit();
+
+        fixture.write("src/deep/lib.rs", "new");
+        fixture.write("src/added.rs", "added");
+        fixture.stage("src/added.rs");
+        fixture.write(".gitignore", "*.log\n");
+        fixture.write("docs/build.log", "ignored");
+
+        // the staged addition and the deeper modification are combined
+        assert_eq!(fixture.status("src").as_deref(), Some("AM"));
+        assert_eq!(fixture.status("src/deep").as_deref(), Some("-M"));
+        // ignored files don't mark their parent
+        assert_eq!(fixture.status("docs").as_deref(), Some("--"));
+    }
+
+    #[test]
+    fn inside_untracked_and_ignored_dirs() {
+        let fixture = Fixture::new("inherited");
+        fixture.write(".gitignore", "target/\n");
+        fixture.stage(".gitignore");
+        fixture.commit();
+
+        fixture.write("new/inner/file.txt", "untracked");
+        fixture.write("target/debug/app", "ignored");
+
+        assert_eq!(fixture.status("new").as_deref(), Some("??"));
+        assert_eq!(fixture.status("new/inner/file.txt").as_deref(), Some("??"));
+        assert_eq!(fixture.status("target").as_deref(), Some("!!"));
+        assert_eq!(fixture.status("target/debug/app").as_deref(), Some("!!"));
+    }
+
+    #[test]
+    fn no_status_for_the_repository_itself() {
+        let fixture = Fixture::new("itself");
+        assert!(fixture.status(".git").is_none());
+        assert!(Git::default().status(&fixture.dir).is_none());
+    }
+}
@@ -0,0 +1,123 @@
+// hides entries matched by .gitignore, .ignore, .git/info/exclude and the global excludes
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use log::warn;
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::git;
+
+// the ignore files of a directory and all of its parents, outermost first
+#[derive(Clone, Default)]
+struct Chain {
+    matchers: Vec<Rc<Gitignore>>,
+    // .gitignore files only count inside a git working tree, like in git
+    in_repo: bool,
+}
+
+// the chains are cached per directory, so every ignore file is only read once
+#[derive(Default)]
+pub struct GitIgnore {
+    chains: RefCell<HashMap<PathBuf, Chain>>,
+}
+
+impl GitIgnore {
+    pub fn is_ignored(&self, entry_path: &Path, is_dir: bool) -> bool {
+        let Some(path) = git::absolute(entry_path) else {
+            return false;
+        };
+        let Some(parent) = path.parent() else {
+            return false;
+        };
+
+        // git never tracks its own directory
+        let chain = self.chain(parent);
+        if chain.in_repo && path.file_name().is_some_and(|name| name == ".git") {
+            return true;
+        }
+
+        // deeper files override their parents, the last match wins within a file,
+        // the parents are checked as well so listing an ignored directory directly hides its content
+        for matcher in chain.matchers.iter().rev() {
+            // the matcher panics for paths outside of its directory
+            if !path.starts_with(matcher.path()) {
+                continue;
+            }
+            let matched = matcher.matched_path_or_any_parents(&path, is_dir);
+            if matched.is_ignore() {
+                return true;
+            }
+            if matched.is_whitelist() {
+                return false;
+            }
+        }
+
+        false
+    }
+
+    fn chain(&self, dir: &Path) -> Chain {
+        if let Some(chain) = self.chains.borrow().get(dir) {
+            return chain.clone();
+        }
+
+        let mut chain = match dir.parent() {
+            Some(parent) => self.chain(parent),
+            None => Chain::default(),
+        };
+
+        // the root of a working tree starts with the excludes that apply to the whole repo,
+        // the ignore files of an outer repository don't apply to a nested one
+        if dir.join(".git").exists() {
+            chain.matchers.clear();
+            chain.in_repo = true;
+            let (global, err) = GitignoreBuilder::new(dir).build_global();
+            if let Some(err) = err {
+                warn!("Unable to read the global git excludes: {err}");
+            }
+            chain.matchers.push(Rc::new(global));
+            if let Some(exclude) = build(dir, &dir.join(".git").join("info").join("exclude")) {
+                chain.matchers.push(Rc::new(exclude));
+            }
+        }
+
+        if chain.in_repo {
+            if let Some(gitignore) = build(dir, &dir.join(".gitignore")) {
+                chain.matchers.push(Rc::new(gitignore));
+            }
+        }
+
+        // .ignore takes precedence over .gitignore in the same directory
+        if let Some(ignore) = build(dir, &dir.join(".ignore")) {
+            chain.matchers.push(Rc::new(ignore));
+        }
+
+        self.chains
+            .borrow_mut()
+            .insert(dir.to_path_buf(), chain.clone());
+
+        chain
+    }
+}
+
+fn build(root: &Path, file: &Path) -> Option<Gitignore> {
+    if !file.is_file() {
+        return None;
+    }
+
+    let mut builder = GitignoreBuilder::new(root);
+    if let Some(err) = builder.add(file) {
+        warn!("Invalid ignore file \'{}\': {}", file.display(), err);
+    }
+
+    match builder.build() {
+        Ok(gitignore) => Some(gitignore),
+        Err(err) => {
+            warn!("Invalid ignore file \'{}\': {}", file.display(), err);
+            None
+        }
+    }
+}
@@ -0,0 +1,117 @@
+// multi-column output like ls -C
+use terminal_size::{terminal_size, Width};
+use unicode_width::UnicodeWidthStr;
+
+use std::env;
+
+const SEPARATOR: usize = 2;
+const DEFAULT_WIDTH: usize = 80;
+
+// cells are printed column by column, or row by row if 'across' is set
+pub fn print_grid(cells: &[String], across: bool) {
+    if cells.is_empty() {
+        return;
+    }
+
+    let widths: Vec<usize> = cells.iter().map(|cell| display_width(cell)).collect();
+    let (rows, column_widths) = fit_columns(&widths, terminal_width(), across);
+    let columns = column_widths.len();
+
+    for row in 0..rows {
+        let mut line = String::new();
+        for (column, column_width) in column_widths.iter().enumerate() {
+            let index = match across {
+                true => row * columns + column,
+                false => column * rows + row,
+            };
+            if index >= cells.len() {
+                break;
+            }
+
+            line.push_str(&cells[index]);
+
+            // no padding after the last cell of a line
+            let next = match across {
+                true => index + 1,
+                false => index + rows,
+            };
+            if column + 1 < columns && next < cells.len() {
+                let padding = column_width - widths[index] + SEPARATOR;
+                line.push_str(&" ".repeat(padding));
+            }
+        }
+        println!("{}", line);
+    }
+}
+
+// returns the number of rows and the width of every column for the widest fitting layout
+fn fit_columns(widths: &[usize], max_width: usize, across: bool) -> (usize, Vec<usize>) {
+    let count = widths.len();
+    // every column is at least as wide as the narrowest cell plus the separator,
+    // so more columns can never fit and don't have to be tried
+    let narrowest = widths.iter().copied().min().unwrap_or(0);
+    let most = ((max_width + SEPARATOR) / (narrowest + SEPARATOR)).clamp(1, count);
+
+    for columns in (1..=most).rev() {
+        let rows = count.div_ceil(columns);
+        // skip layouts that would leave whole columns empty
+        if !across && (columns - 1) * rows >= count {
+            continue;
+        }
+
+        let mut column_widths = vec![0; columns];
+        for (index, width) in widths.iter().enumerate() {
+            let column = match across {
+                true => index % columns,
+                false => index / rows,
+            };
+            column_widths[column] = column_widths[column].max(*width);
+        }
+
+        let total: usize = column_widths.iter().sum::<usize>() + SEPARATOR * (columns - 1);
+        if total <= max_width || columns == 1 {
+            return (rows, column_widths);
+        }
+    }
+
+    (count, vec![widths.iter().copied().max().unwrap_or(0)])
+}
+
+fn terminal_width() -> usize {
+    if let Some((Width(width), _)) = terminal_size() {
+        return width as usize;
+    }
+
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+// width of a string on the terminal, ignoring ansi escape sequences
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(strip_ansi(text).as_str())
+}
+
+fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+
+        // skip the control sequence up to its final byte
+        if let Some('[') = chars.next() {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+
+    stripped
+}
@@ -0,0 +1,185 @@
+// nerd font icons in front of the names
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::BTreeMap,
+    io::{self, IsTerminal},
+};
+
+use crate::{
+    category::{self, Category},
+    FileData,
+};
+
+const DIR: &str = "\u{f07b}";
+const FILE: &str = "\u{f15b}";
+const SYMLINK: &str = "\u{f0c1}";
+const BROKEN_LINK: &str = "\u{f127}";
+
+// well-known file names, compared in lowercase
+const FILES: &[(&str, &str)] = &[
+    (".git", "\u{f1d3}"),
+    (".gitattributes", "\u{f1d3}"),
+    (".gitignore", "\u{f1d3}"),
+    (".gitmodules", "\u{f1d3}"),
+    ("cargo.lock", "\u{e7a8}"),
+    ("cargo.toml", "\u{e7a8}"),
+    ("dockerfile", "\u{f308}"),
+    ("license", "\u{e60a}"),
+    ("makefile", "\u{e779}"),
+    ("readme", "\u{f405}"),
+];
+
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("c", "\u{e61e}"),
+    ("cpp", "\u{e61d}"),
+    ("css", "\u{e749}"),
+    ("go", "\u{e626}"),
+    ("h", "\u{f0fd}"),
+    ("html", "\u{e736}"),
+    ("java", "\u{e738}"),
+    ("js", "\u{e74e}"),
+    ("json", "\u{e60b}"),
+    ("lock", "\u{f023}"),
+    ("lua", "\u{e620}"),
+    ("md", "\u{e609}"),
+    ("pdf", "\u{f1c1}"),
+    ("py", "\u{e606}"),
+    ("rs", "\u{e7a8}"),
+    ("sh", "\u{f489}"),
+    ("toml", "\u{e615}"),
+    ("ts", "\u{e628}"),
+    ("vim", "\u{e62b}"),
+];
+
+// fallback for files without a specific icon, by their colour category
+const CATEGORIES: &[(&str, &str)] = &[
+    ("notes-info", "\u{f15c}"),
+    ("notes-important", "\u{f15c}"),
+    ("notes-warn", "\u{f15c}"),
+    ("exec", "\u{f489}"),
+    ("config", "\u{e615}"),
+    ("source", "\u{f121}"),
+    ("office", "\u{f0f6}"),
+    ("other", "\u{f016}"),
+    ("media", "\u{f1c5}"),
+    ("archive", "\u{f1c6}"),
+];
+
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl IconMode {
+    pub fn from_arg(arg: &str) -> IconMode {
+        match arg {
+            "always" => IconMode::Always,
+            "never" => IconMode::Never,
+            _ => IconMode::Auto,
+        }
+    }
+
+    // auto shows icons only when writing to a terminal
+    pub fn enabled(self) -> bool {
+        match self {
+            IconMode::Always => true,
+            IconMode::Never => false,
+            IconMode::Auto => io::stdout().is_terminal(),
+        }
+    }
+}
+
+// icons from the config file replace or add to the bundled ones,
+// 'types' covers 'file', 'dir', 'symlink' and 'broken-link'
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IconConfig {
+    pub files: BTreeMap<String, String>,
+    pub extensions: BTreeMap<String, String>,
+    pub categories: BTreeMap<String, String>,
+    pub types: BTreeMap<String, String>,
+}
+
+pub struct Icons {
+    files: BTreeMap<String, String>,
+    extensions: BTreeMap<String, String>,
+    categories: BTreeMap<String, String>,
+    types: BTreeMap<String, String>,
+}
+
+impl Icons {
+    pub fn new(config: &IconConfig) -> Icons {
+        let types = [
+            ("file", FILE),
+            ("dir", DIR),
+            ("symlink", SYMLINK),
+            ("broken-link", BROKEN_LINK),
+        ];
+
+        Icons {
+            files: merge(FILES, &config.files),
+            extensions: merge(EXTENSIONS, &config.extensions),
+            categories: merge(CATEGORIES, &config.categories),
+            types: merge(&types, &config.types),
+        }
+    }
+
+    // file names take precedence over extensions, extensions over categories
+    pub fn icon(&self, entry: &FileData, categories: &[Category]) -> &str {
+        if entry.is_symlink() {
+            return match entry.broken_link {
+                true => self.get_type("broken-link"),
+                false => self.get_type("symlink"),
+            };
+        }
+
+        let name = entry.name.to_lowercase();
+        let stem = name.split('.').next().unwrap_or_default();
+        let by_name = self
+            .files
+            .get(&name)
+            .or_else(|| self.files.get(stem).filter(|_| !stem.is_empty()));
+        if let Some(icon) = by_name {
+            return icon;
+        }
+
+        if entry.is_dir() {
+            return self.get_type("dir");
+        }
+
+        let extension = entry.file_extension.to_lowercase();
+        if let Some(icon) = self.extensions.get(&extension) {
+            return icon;
+        }
+
+        category::name(categories, &entry.name, &entry.file_extension)
+            .and_then(|category| self.categories.get(&category.to_lowercase()))
+            .map(|icon| icon.as_str())
+            .unwrap_or_else(|| self.get_type("file"))
+    }
+
+    fn get_type(&self, name: &str) -> &str {
+        self.types
+            .get(name)
+            .map(|icon| icon.as_str())
+            .unwrap_or(FILE)
+    }
+}
+
+fn merge(bundled: &[(&str, &str)], config: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    let mut icons: BTreeMap<String, String> = bundled
+        .iter()
+        .map(|(key, icon)| (key.to_string(), icon.to_string()))
+        .collect();
+    icons.extend(
+        config
+            .iter()
+            .map(|(key, icon)| (key.to_lowercase(), icon.clone())),
+    );
+
+    icons
+}
@@ -0,0 +1,114 @@
+// colours from the LS_COLORS environment variable, as set by dircolors
+use std::{collections::HashMap, env};
+
+use crate::{platform, FileData};
+
+pub struct LsColors {
+    // file type keys like 'di', 'ln' or 'ex'
+    types: HashMap<String, String>,
+    // lowercase suffixes from patterns like '*.rs' or '*README'
+    suffixes: Vec<(String, String)>,
+}
+
+impl LsColors {
+    // returns None if LS_COLORS is not set or contains nothing usable
+    pub fn from_env() -> Option<LsColors> {
+        let value = env::var("LS_COLORS").ok()?;
+        let ls_colors = LsColors::parse(&value);
+
+        match ls_colors.types.is_empty() && ls_colors.suffixes.is_empty() {
+            true => None,
+            false => Some(ls_colors),
+        }
+    }
+
+    fn parse(value: &str) -> LsColors {
+        let mut types = HashMap::new();
+        let mut suffixes = Vec::new();
+
+        for rule in value.split(':') {
+            let Some((key, code)) = rule.split_once('=') else {
+                continue;
+            };
+
+            // only plain sgr codes like '01;34' are used, anything else is ignored
+            if code.is_empty() || !code.chars().all(|c| c.is_ascii_digit() || c == ';') {
+                continue;
+            }
+
+            match key.strip_prefix('*') {
+                Some(suffix) => suffixes.push((suffix.to_lowercase(), code.to_string())),
+                None => {
+                    types.insert(key.to_string(), code.to_string());
+                }
+            }
+        }
+
+        LsColors { types, suffixes }
+    }
+
+    // the sgr code for an entry, following the lookup order of GNU ls
+    fn code(&self, entry: &FileData) -> Option<&str> {
+        if entry.is_dir() {
+            return self.get("di");
+        }
+
+        if entry.is_symlink() {
+            return match entry.broken_link {
+                true => self.get("or").or(self.get("ln")),
+                false => self.get("ln"),
+            };
+        }
+
+        if !entry.is_file() {
+            return match platform::special_file_key(entry.filetype) {
+                Some(key) => self.get(key),
+                None => None,
+            };
+        }
+
+        let executable = entry
+            .permissions
+            .as_ref()
+            .is_some_and(platform::is_executable);
+        if executable {
+            if let Some(code) = self.get("ex") {
+                return Some(code);
+            }
+        }
+
+        // later patterns take precedence like in GNU ls
+        let name = entry.name.to_lowercase();
+        if let Some((_, code)) = self
+            .suffixes
+            .iter()
+            .rev()
+            .find(|(suffix, _)| name.ends_with(suffix.as_str()))
+        {
+            return Some(code);
+        }
+
+        self.get("fi")
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.types.get(key).map(|code| code.as_str())
+    }
+
+    // entries without a matching rule are printed as they are
+    pub fn paint(&self, text: &str, entry: &FileData) -> String {
+        paint(text, self.code(entry))
+    }
+
+    // the missing target of a broken link, 'mi' falls back to 'or' like in GNU ls
+    pub fn paint_missing(&self, text: &str) -> String {
+        paint(text, self.get("mi").or(self.get("or")))
+    }
+}
+
+fn paint(text: &str, code: Option<&str>) -> String {
+    match code {
+        Some(code) => format!("\x1b[{code}m{text}\x1b[0m"),
+        None => text.to_string(),
+    }
+}
@@ -1,62 +1,44 @@
 // TODO cleanup - refactor - extract stuff into separate functions
-use clap::{Arg, ArgAction, Command};
+mod category;
+mod colour;
+mod config;
+mod export;
+mod filter;
+mod git;
+mod gitignore;
+mod grid;
+mod icons;
+mod lscolors;
+mod platform;
+mod size;
+mod sort;
+mod theme;
+mod time;
+
+use category::Category;
+use clap::{parser::ValueSource, Arg, ArgAction, ArgMatches, Command};
+use colour::{ColourMode, Depth};
 use flexi_logger::{detailed_format, Duplicate, FileSpec, Logger};
+use icons::{IconMode, Icons};
 use log::{error, info, warn};
-use owo_colors::OwoColorize;
+use lscolors::LsColors;
+use owo_colors::{OwoColorize, Style as OwoStyle};
+use size::SizeStyle;
+use sort::{GroupDirs, SortBy};
+use theme::Theme;
+use time::{TimeField, TimeStyle};
 
 use std::{
     env,
-    fs::{self, FileType, Permissions},
-    io,
-    os::windows::prelude::MetadataExt,
+    fs::{self, FileType, Metadata, Permissions},
+    io::{self, IsTerminal},
+    num::NonZeroUsize,
     path::{Path, PathBuf},
     process,
-    time::SystemTime,
+    sync::atomic::{AtomicI32, Ordering},
+    time::{Duration, SystemTime},
 };
 
-const KB: u64 = 1024;
-const MB: u64 = 1024_u64.pow(2);
-const GB: u64 = 1024_u64.pow(3);
-const TB: u64 = 1024_u64.pow(4);
-
-// on white
-const NOTES_INFO: &[&'static str] = &["todo", "info", "log"];
-// on yellow
-const NOTES_IMPORTANT: &[&'static str] = &["readme", "important"];
-// on red
-const NOTES_WARN: &[&'static str] = &["fixme", "warn", "warning"];
-// red
-const EXECUTABLE: &[&'static str] = &["exe", "msi", "bat"];
-// yellow
-const SPECIAL: &[&'static str] = &[
-    "md", "cgf", "conf", "config", "ini", "json", "tml", "toml", "yaml", "yml", "csv", "markdown",
-    "org", "rst", "xml", "ron",
-];
-// green
-const PROGRAMMING: &[&'static str] = &[
-    "py", "pl", "rs", "c", "cpp", "awk", "vb", "cabal", "clj", "cs", "csx", "css", "h", "hpp",
-    "dart", "ex", "exs", "elc", "elm", "erl", "fs", "go", "hs", "ipynb", "java", "bsh", "js", "jl",
-    "kt", "tex", "lisp", "lua", "matlab", "pas", "p", "php", "ps1", "r", "rb", "scala", "sh",
-    "bash", "zsh", "fish", "sql", "swift", "ts", "tsx", "vim", "cmake", "make",
-];
-// pink
-const MEDIA: &[&'static str] = &[
-    "bmp", "gif", "jpeg", "jpg", "png", "svg", "avi", "mp4", "wmv", "wma", "mp3", "wav", "mid",
-    "ttf", "m4a",
-];
-// red
-const OFFICE: &[&'static str] = &[
-    "doc", "docx", "epub", "odt", "pdf", "ps", "xls", "xlsx", "ods", "xlr", "ppt", "pptx", "odp",
-    "pps", "ics",
-];
-// cyan
-const ARCHIVES: &[&'static str] = &[
-    "apk", "deb", "rpm", "xbps", "bag", "bin", "dmg", "img", "iso", "toast", "vcd", "7z", "arj",
-    "gz", "zip", "pkg", "tar", "jar", "rar", "tgz", "z", "zst", "xz", "tgz",
-];
-// darkgray
-const OTHER: &[&'static str] = &["~", "git", "gitignore", "tmp", "lock"];
-
 // COLOURS
 // -------
 // OLD
@@ -81,136 +63,168 @@ const OTHER: &[&'static str] = &["~", "git", "gitignore", "tmp", "lock"];
 //   darkergray: '36384a'           54,56,74
 //   silver: 'bcb6ba'               97,88,111
 
-struct Perms {
-    read: String,
-    write: String,
-}
+// like ls: 1 if single entries or subdirectories could not be read,
+// 2 if a given path could not be listed at all
+// the listing continues anyway and exits with the highest status afterwards
+static EXIT_STATUS: AtomicI32 = AtomicI32::new(0);
 
 struct FileData {
     name: String,
     path: String,
-    filetype: String,
-    filesize: Vec<String>,
+    // metadata is missing for entries that could not be read
+    filetype: Option<FileType>,
+    filesize: u64,
     hidden: bool,
-    modified: String,
-    permissions: Perms,
+    modified: Option<SystemTime>,
+    accessed: Option<SystemTime>,
+    created: Option<SystemTime>,
+    changed: Option<SystemTime>,
+    permissions: Option<Permissions>,
+    // mode, owner, group and link count on unix
+    unix: Option<platform::UnixMeta>,
+    // FILE_ATTRIBUTE_* bits on windows
+    attributes: Option<u32>,
+    system: bool,
     file_extension: String,
+    symlink_target: Option<PathBuf>,
+    broken_link: bool,
+    unreadable: bool,
 }
 
 impl FileData {
     fn new(
         name: String,
         path: String,
-        filetype: FileType,
-        filesize: u64,
+        metadata: &Metadata,
         hidden: bool,
-        modified: u64,
-        permissions: Permissions,
         file_extension: String,
     ) -> FileData {
-        let mut ftype = String::new();
-        match filetype.is_file() {
-            true => {
-                ftype.push_str("file");
-            }
-            false => {
-                match filetype.is_dir() {
-                    true => ftype.push_str("dir"),
-                    false => ftype.push_str("symlink"),
-                };
-            }
+        let attributes = platform::attributes(metadata);
+        FileData {
+            name,
+            path,
+            filetype: Some(metadata.file_type()),
+            filesize: platform::file_size(metadata),
+            hidden,
+            modified: metadata.modified().ok(),
+            accessed: metadata.accessed().ok(),
+            created: metadata.created().ok(),
+            changed: platform::changed(metadata),
+            permissions: Some(metadata.permissions()),
+            unix: platform::unix_meta(metadata),
+            attributes,
+            system: platform::is_system(attributes),
+            file_extension,
+            symlink_target: None,
+            broken_link: false,
+            unreadable: false,
         }
+    }
 
-        let mut fsize: Vec<String> = Vec::new();
-        if filesize <= 0 {
-            fsize.push("-".to_string());
-        } else {
-            match filesize {
-                s if s >= TB => {
-                    let size = ((filesize as f64 / TB as f64) * 10.0).round() / 10.0;
-                    fsize.push(size.to_string());
-                    fsize.push("T".to_string());
-                }
-                s if s >= GB && s < TB => {
-                    let size = ((filesize as f64 / GB as f64) * 10.0).round() / 10.0;
-                    fsize.push(size.to_string());
-                    fsize.push("G".to_string());
-                }
-                s if s >= MB && s < GB => {
-                    let size = ((filesize as f64 / MB as f64) * 10.0).round() / 10.0;
-                    fsize.push(size.to_string());
-                    fsize.push("M".to_string());
-                }
-                s if s >= KB && s < MB => {
-                    let size = ((filesize as f64 / KB as f64) * 10.0).round() / 10.0;
-                    fsize.push(size.to_string());
-                    fsize.push("K".to_string());
-                }
-                s if s < KB => {
-                    fsize.push(filesize.to_string());
-                    fsize.push("B".to_string());
-                }
-                _ => {
-                    fsize.push("-".to_string());
-                }
-            }
+    // placeholder for an entry whose metadata could not be read
+    fn unreadable(
+        name: String,
+        path: String,
+        filetype: Option<FileType>,
+        file_extension: String,
+    ) -> FileData {
+        FileData {
+            hidden: name.starts_with('.'),
+            name,
+            path,
+            filetype,
+            filesize: 0,
+            modified: None,
+            accessed: None,
+            created: None,
+            changed: None,
+            permissions: None,
+            unix: None,
+            attributes: None,
+            system: false,
+            file_extension,
+            symlink_target: None,
+            broken_link: false,
+            unreadable: true,
         }
+    }
 
-        let mut modified_human_readable = String::new();
-        match modified {
-            0..=59 => {
-                modified_human_readable.push_str(modified.to_string().as_str());
-                modified_human_readable.push_str(" secs ago");
-            }
-            60..=3599 => {
-                let minutes = ((modified as f64 / 60.0) as f64).round();
-                modified_human_readable.push_str(minutes.to_string().as_str());
-                modified_human_readable.push_str(" mins ago");
-            }
-            3600..=86399 => {
-                let hours = ((modified as f64 / 3600.0) as f64).round();
-                modified_human_readable.push_str(hours.to_string().as_str());
-                modified_human_readable.push_str("  hrs ago");
-            }
-            86400.. => {
-                let days = ((modified as f64 / 86400.0) as f64).round();
-                modified_human_readable.push_str(days.to_string().as_str());
-                modified_human_readable.push_str(" days ago");
-            }
+    fn time(&self, field: TimeField) -> Option<SystemTime> {
+        match field {
+            TimeField::Modified => self.modified,
+            TimeField::Created => self.created,
+            TimeField::Accessed => self.accessed,
+            TimeField::Changed => self.changed,
         }
+    }
 
-        let mut perms = Perms {
-            read: String::new(),
-            write: String::new(),
-        };
-        match permissions.readonly() {
-            true => {
-                perms.read = String::from("r");
-                perms.write = String::from("-");
-            }
-            false => {
-                perms.read = String::from("r");
-                perms.write = String::from("w");
-            }
-        }
+    fn is_file(&self) -> bool {
+        self.filetype.is_some_and(|filetype| filetype.is_file())
+    }
 
-        FileData {
-            name: name,
-            path: path,
-            filetype: ftype,
-            filesize: fsize,
-            hidden: hidden,
-            modified: modified_human_readable,
-            permissions: perms,
-            file_extension: file_extension,
-        }
+    fn is_dir(&self) -> bool {
+        self.filetype.is_some_and(|filetype| filetype.is_dir())
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.filetype.is_some_and(|filetype| filetype.is_symlink())
     }
 }
 
+struct Options {
+    long: bool,
+    hidden: bool,
+    fullpath: bool,
+    // resolved from --colour, NO_COLOR, CLICOLOR_FORCE and the terminal
+    colour: bool,
+    depth: Depth,
+    files: bool,
+    dirs: bool,
+    tree: bool,
+    recursive: bool,
+    // maximum depth to descend into when listing as tree or recursive
+    level: Option<usize>,
+    sort: SortBy,
+    reverse: bool,
+    // the timestamp shown in the long listing and used for sorting by time
+    time_field: TimeField,
+    time_style: TimeStyle,
+    size_style: SizeStyle,
+    group_dirs: GroupDirs,
+    // print machine-readable output instead of the terminal formats
+    export: Option<export::Format>,
+    // show the metadata of the symlink target instead of the link itself
+    dereference: bool,
+    theme: Theme,
+    // file categories with their style from the theme or the config
+    categories: Vec<Category>,
+    // file name colours from LS_COLORS, used instead of the theme if set
+    ls_colors: Option<LsColors>,
+    // icons in front of the names, if enabled
+    icons: Option<Icons>,
+    // git status column in the long listing, if enabled
+    git: Option<git::Git>,
+    // hide entries matched by ignore files, if enabled
+    git_ignore: Option<gitignore::GitIgnore>,
+    // show the mode as octal number instead of rwxrwxrwx
+    octal: bool,
+    // show entries with the windows system attribute
+    system: bool,
+    owners: platform::Owners,
+    // --ignore-glob, --only and --regex
+    name_filter: filter::NameFilter,
+    // --larger-than, --smaller-than, --newer-than, --older-than, --ext and --type
+    meta_filter: filter::MetaFilter,
+    // print the short output in columns, only when writing to a terminal
+    grid: bool,
+    across: bool,
+}
+
 fn main() {
     // handle Ctrl+C
     ctrlc::set_handler(move || {
-        println!("{}", "Received Ctrl-C!".italic());
+        // plain, the colour options are not known yet
+        println!("Received Ctrl-C!");
         process::exit(0)
     })
     .expect("Error setting Ctrl-C handler");
@@ -237,66 +251,243 @@ fn main() {
 
     // handle arguments
     let matches = sl().get_matches();
-    let mut long_flag = matches.get_flag("long");
-    let mut hidden_flag = matches.get_flag("hidden");
-    let mut colour_flag = matches.get_flag("colour");
-    let mut fullpath_flag = matches.get_flag("fullpath");
-    let mut files_flag = matches.get_flag("files");
-    let mut dirs_flag = matches.get_flag("dirs");
+    // default flags from the config file, flags on the command line take precedence,
+    // --override ignores the config file and all arguments given before it
     let override_flag = matches.get_flag("override");
+    let config = match override_flag {
+        true => config::Config::default(),
+        false => config::load(&config_dir),
+    };
+    let given = |id: &str| given(&matches, id);
+    let flag = |id: &str| matches.get_flag(id) && given(id);
+    let long_flag = flag("long") || config.long.unwrap_or(false);
+    let hidden_flag = flag("hidden") || config.hidden.unwrap_or(false);
+    let colour_mode = matches
+        .get_one::<String>("colour")
+        .filter(|_| given("colour"))
+        .map(|arg| ColourMode::from_arg(arg))
+        .or(config.colour)
+        .unwrap_or(ColourMode::Auto);
+    let fullpath_flag = flag("fullpath") || config.fullpath.unwrap_or(false);
+    let files_flag = flag("files");
+    let dirs_flag = flag("dirs");
+    let tree_flag = flag("tree");
+    let recursive_flag = flag("recursive");
+    let level = matches
+        .get_one::<u64>("level")
+        .filter(|_| given("level"))
+        .map(|level| *level as usize)
+        .or(config.level.map(NonZeroUsize::get));
+    let sort_by = matches
+        .get_one::<String>("sort")
+        .filter(|_| given("sort"))
+        .map(|arg| SortBy::from_arg(arg))
+        .or(config.sort)
+        .unwrap_or(SortBy::Name);
+    let reverse_flag = flag("reverse") || config.reverse.unwrap_or(false);
+    let dereference_flag = flag("dereference") || config.dereference.unwrap_or(false);
+    let oneline_flag = flag("oneline") || config.oneline.unwrap_or(false);
+    let across_flag = flag("across") || config.across.unwrap_or(false);
+    let time_field = matches
+        .get_one::<String>("time")
+        .filter(|_| given("time"))
+        .map(|arg| TimeField::from_arg(arg))
+        .or(config.time)
+        .unwrap_or(TimeField::Modified);
+    let time_style = matches
+        .get_one::<TimeStyle>("time-style")
+        .filter(|_| given("time-style"))
+        .cloned()
+        .or(config.time_style.clone())
+        .unwrap_or(TimeStyle::Relative);
+    let size_style = if let Some(block_size) = matches
+        .get_one::<SizeStyle>("block-size")
+        .filter(|_| given("block-size"))
+    {
+        block_size.clone()
+    } else if flag("bytes") {
+        SizeStyle::Bytes
+    } else if flag("si") {
+        SizeStyle::Si
+    } else if flag("binary") {
+        SizeStyle::Binary
+    } else {
+        config.size_style.clone().unwrap_or(SizeStyle::Short)
+    };
+    let system_flag = flag("system") || config.system.unwrap_or(false);
+    let octal_flag = flag("octal") || config.octal.unwrap_or(false);
+    let git_flag = flag("git") || config.git.unwrap_or(false);
+    let git_ignore_flag = flag("git-ignore") || config.git_ignore.unwrap_or(false);
+    let export = if flag("json") {
+        Some(export::Format::Json)
+    } else if flag("ndjson") {
+        Some(export::Format::Ndjson)
+    } else {
+        matches
+            .get_one::<String>("format")
+            .filter(|_| given("format"))
+            .map(|arg| export::Format::from_arg(arg))
+            .or(config.format)
+    };
+    let group_dirs = matches
+        .get_one::<String>("group-dirs")
+        .filter(|_| given("group-dirs"))
+        .map(|arg| GroupDirs::from_arg(arg))
+        .or(config.group_dirs)
+        .unwrap_or(GroupDirs::None);
+    let theme_name = matches
+        .get_one::<String>("theme")
+        .filter(|_| given("theme"))
+        .or(config.theme.as_ref())
+        .cloned();
+    let icon_mode = matches
+        .get_one::<String>("icons")
+        .filter(|_| given("icons"))
+        .map(|arg| IconMode::from_arg(arg))
+        .or(config.icons)
+        .unwrap_or(IconMode::Never);
+    let ls_colors_flag = !flag("no-ls-colors") && config.ls_colors.unwrap_or(true);
+    let ignore_globs: Vec<String> = match matches
+        .get_many::<String>("ignore-glob")
+        .filter(|_| given("ignore-glob"))
+    {
+        Some(args) => args.cloned().collect(),
+        None => config.ignore_glob.clone().unwrap_or_default(),
+    };
+    let only_globs: Vec<String> = matches
+        .get_many::<String>("only")
+        .filter(|_| given("only"))
+        .map(|args| args.cloned().collect())
+        .unwrap_or_default();
+    let regex = matches
+        .get_one::<String>("regex")
+        .filter(|_| given("regex"))
+        .cloned();
+    let now = SystemTime::now();
+    let meta_filter = filter::MetaFilter {
+        larger_than: matches
+            .get_one::<u64>("larger-than")
+            .filter(|_| given("larger-than"))
+            .copied(),
+        smaller_than: matches
+            .get_one::<u64>("smaller-than")
+            .filter(|_| given("smaller-than"))
+            .copied(),
+        newer_than: matches
+            .get_one::<Duration>("newer-than")
+            .filter(|_| given("newer-than"))
+            .and_then(|age| now.checked_sub(*age)),
+        older_than: matches
+            .get_one::<Duration>("older-than")
+            .filter(|_| given("older-than"))
+            .map(|age| now.checked_sub(*age).unwrap_or(SystemTime::UNIX_EPOCH)),
+        extensions: matches
+            .get_many::<String>("ext")
+            .filter(|_| given("ext"))
+            .map(|args| {
+                args.map(|ext| ext.trim_start_matches('.').to_lowercase())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        types: matches
+            .get_many::<String>("type")
+            .filter(|_| given("type"))
+            .map(|args| args.filter_map(|arg| arg.chars().next()).collect())
+            .unwrap_or_default(),
+    };
+    let name_filter = filter::NameFilter::new(&ignore_globs, &only_globs, regex.as_deref())
+        .unwrap_or_else(|err| {
+            error!("{err}");
+            process::exit(2);
+        });
 
-    // if override flag is set -> reset everything to default values
-    if override_flag {
-        files_flag = false;
-        dirs_flag = false;
-        hidden_flag = false;
-        long_flag = false;
-        fullpath_flag = false;
-        colour_flag = false;
-    }
-
-    if let Some(arg) = matches.get_one::<String>("path") {
-        let mut path = Path::new(&arg).to_path_buf();
-
-        if arg.is_empty() {
-            let current_dir = env::current_dir().unwrap_or_else(|err| {
-                error!("Unable to get current directory: {err}");
-                process::exit(1);
-            });
-            path.push(current_dir);
-        }
+    let theme = match theme_name {
+        Some(name) => theme::load(&name, &config_dir),
+        None => Theme::default(),
+    };
 
-        if let Err(err) = list_dirs(
-            path.clone(),
-            long_flag,
-            hidden_flag,
-            fullpath_flag,
-            colour_flag,
-            files_flag,
-            dirs_flag,
-        ) {
-            match err.kind() {
-                io::ErrorKind::NotFound => {
-                    info!("\'{}\' not found: {}", path.display(), err);
-                }
-                io::ErrorKind::PermissionDenied => {
-                    warn!("Permission denied for \'{}\': {}", path.display(), err);
-                }
-                _ => {
-                    error!(
-                        "Unable to get the entries of the directory \'{}\': {}",
-                        path.display(),
-                        err
+    let options = Options {
+        long: long_flag,
+        hidden: hidden_flag,
+        fullpath: fullpath_flag,
+        colour: colour_mode.enabled(),
+        depth: colour::depth(),
+        files: files_flag,
+        dirs: dirs_flag,
+        tree: tree_flag,
+        recursive: recursive_flag,
+        level,
+        sort: sort_by,
+        reverse: reverse_flag,
+        time_field,
+        time_style,
+        size_style,
+        group_dirs,
+        export,
+        dereference: dereference_flag,
+        categories: category::build(&theme, &config.categories.clone().unwrap_or_default()),
+        theme,
+        ls_colors: match ls_colors_flag {
+            true => LsColors::from_env(),
+            false => None,
+        },
+        icons: match icon_mode.enabled() {
+            true => Some(Icons::new(&config.icon_map.clone().unwrap_or_default())),
+            false => None,
+        },
+        git: match git_flag && long_flag {
+            true => Some(git::Git::default()),
+            false => None,
+        },
+        git_ignore: match git_ignore_flag {
+            true => Some(gitignore::GitIgnore::default()),
+            false => None,
+        },
+        octal: octal_flag,
+        system: system_flag,
+        owners: platform::Owners::default(),
+        name_filter,
+        meta_filter,
+        grid: !oneline_flag && io::stdout().is_terminal(),
+        across: across_flag,
+    };
+
+    let paths: Vec<PathBuf> = matches
+        .get_many::<String>("path")
+        .map(|args| args.flat_map(|arg| expand_path(arg)).collect())
+        .unwrap_or_default();
+
+    if !paths.is_empty() {
+        list_paths(paths, &options);
+    } else {
+        match matches.subcommand() {
+            Some(("config", config_matches)) => {
+                let path = config::config_path(&config_dir);
+                if config_matches.get_flag("path") {
+                    println!("{}", path.display());
+                } else {
+                    println!(
+                        "{} {}",
+                        styled(
+                            "Config location:",
+                            OwoStyle::new().italic().dimmed(),
+                            options.colour
+                        ),
+                        path.display()
                     );
-                    process::exit(1);
+                    print!("{}", config::show(&config::load(&config_dir)));
                 }
             }
-        }
-    } else {
-        match matches.subcommand() {
             Some(("log", _)) => {
-                if let Ok(logs) = show_log_file(&config_dir) {
-                    println!("{}", "Available logs:".bold().yellow());
+                if let Ok(logs) = show_log_file(&config_dir, options.colour) {
+                    println!(
+                        "{}",
+                        styled(
+                            "Available logs:",
+                            OwoStyle::new().bold().yellow(),
+                            options.colour
+                        )
+                    );
                     println!("{}", logs);
                 } else {
                     error!("Unable to read logs");
@@ -309,57 +500,125 @@ fn main() {
                     process::exit(1);
                 });
 
-                let path = Path::new(&current_dir).to_path_buf();
-
-                if let Err(err) = list_dirs(
-                    path.clone(),
-                    long_flag,
-                    hidden_flag,
-                    fullpath_flag,
-                    colour_flag,
-                    files_flag,
-                    dirs_flag,
-                ) {
-                    match err.kind() {
-                        io::ErrorKind::NotFound => {
-                            info!("\'{}\' not found: {}", path.display(), err);
-                        }
-                        io::ErrorKind::PermissionDenied => {
-                            warn!("Permission denied for \'{}\': {}", path.display(), err);
-                        }
-                        _ => {
-                            error!(
-                                "Unable to get the entries of the directory \'{}\': {}",
-                                path.display(),
-                                err
-                            );
-                            process::exit(1);
-                        }
-                    }
-                }
+                list_paths(vec![current_dir], &options);
             }
         }
     }
+
+    let status = EXIT_STATUS.load(Ordering::Relaxed);
+    if status > 0 {
+        process::exit(status);
+    }
 }
 
 fn sl() -> Command {
     Command::new("sl")
         .bin_name("sl")
-        .before_help(format!(
-            "{}\n{}",
-            "SIMPLE LS".bold().truecolor(250, 0, 104),
-            "Leann Phydon <leann.phydon@gmail.com>".italic().dimmed()
-        ))
+        // plain, the help is printed before --colour is read
+        .before_help("SIMPLE LS\nLeann Phydon <leann.phydon@gmail.com>")
         .about("List directory entries")
         // TODO update version
         .version("1.1.9")
         .author("Leann Phydon <leann.phydon@gmail.com>")
+        .arg(
+            Arg::new("newer-than")
+                .long("newer-than")
+                .help("Show only entries modified within the age, e.g. 2d")
+                .long_help(format!(
+                    "{}\n{}",
+                    "Show only entries modified within the age",
+                    "Ages are given with one of the units s, min, h, d, w, mo, y like 30min, 2d or 1y"
+                ))
+                .value_name("AGE")
+                .value_parser(filter::parse_age)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("older-than")
+                .long("older-than")
+                .help("Show only entries modified before the age, e.g. 1y")
+                .value_name("AGE")
+                .value_parser(filter::parse_age)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("octal")
+                .long("octal")
+                .help("Show the permissions as octal number like 0755 in the long listing")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("oneline")
+                .short('1')
+                .long("oneline")
+                .help("Show one entry per line")
+                .long_help(format!(
+                    "{}\n{}",
+                    "Show one entry per line",
+                    "This is the default when the output is not a terminal"
+                ))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("across")
+                .short('x')
+                .long("across")
+                .help("Fill the columns row by row instead of column by column")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("oneline"),
+        )
+        .arg(
+            Arg::new("binary")
+                .long("binary")
+                .help("Show sizes with binary units like KiB and MiB in the long listing")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["bytes", "si", "block-size"]),
+        )
+        .arg(
+            Arg::new("block-size")
+                .long("block-size")
+                .help("Show sizes in blocks of SIZE in the long listing")
+                .long_help(format!(
+                    "{}\n{}\n{}",
+                    "Show sizes in blocks of SIZE in the long listing, rounded up like GNU ls",
+                    "SIZE is a number with an optional unit K, M, G, T (1024-based) or KB, MB, GB, TB (1000-based)",
+                    "A unit without a number like 'M' is shown behind the sizes"
+                ))
+                .value_name("SIZE")
+                .value_parser(SizeStyle::parse)
+                .action(ArgAction::Set)
+                .conflicts_with_all(["bytes", "si"]),
+        )
+        .arg(
+            Arg::new("bytes")
+                .long("bytes")
+                .help("Show the exact sizes in bytes in the long listing")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("si"),
+        )
         .arg(
             Arg::new("colour")
                 .short('c')
                 .long("colour")
                 .visible_alias("color")
-                .help("Show coloured output")
+                .help("When to colour the output")
+                .long_help(format!(
+                    "{}\n{}\n{}",
+                    "When to colour the output [default: auto]",
+                    "Auto colours only when writing to a terminal, unless NO_COLOR or CLICOLOR_FORCE are set",
+                    "'-c' without a value is the same as '--colour=always'"
+                ))
+                .value_name("WHEN")
+                .value_parser(["auto", "always", "never"])
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("always")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("dereference")
+                .long("dereference")
+                .help("Show the information of the symlink target instead of the link itself")
                 .action(ArgAction::SetTrue),
         )
         .arg(
@@ -371,6 +630,14 @@ fn sl() -> Command {
                 .action(ArgAction::SetTrue)
                 .conflicts_with("files"),
         )
+        .arg(
+            Arg::new("ext")
+                .long("ext")
+                .help("Show only files with one of the extensions, e.g. rs,toml")
+                .value_name("EXT")
+                .value_delimiter(',')
+                .action(ArgAction::Append),
+        )
         .arg(
             Arg::new("files")
                 .short('f')
@@ -379,6 +646,20 @@ fn sl() -> Command {
                 .help("Show only files")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help("Print the entries in a machine-readable format")
+                .long_help(format!(
+                    "{}\n{}",
+                    "Print the entries in a machine-readable format",
+                    "csv and tsv contain the columns of the long listing [type, permissions, links, owner, group, size, modified, name, path]"
+                ))
+                .value_name("FORMAT")
+                .value_parser(["json", "ndjson", "csv", "tsv"])
+                .action(ArgAction::Set)
+                .conflicts_with_all(["json", "ndjson"]),
+        )
         .arg(
             Arg::new("fullpath")
                 .short('F')
@@ -386,6 +667,37 @@ fn sl() -> Command {
                 .help("Show the complete path instead of just the filename")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("git")
+                .long("git")
+                .help("Show the git status of every entry in the long listing")
+                .long_help(format!(
+                    "{}\n{}\n{}",
+                    "Show the git status of every entry in the long listing",
+                    "The two columns are the index and the working tree like in 'git status --short'",
+                    "Directories show the combined status of their content"
+                ))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("git-ignore")
+                .long("git-ignore")
+                .help("Hide entries that are ignored by git")
+                .long_help(format!(
+                    "{}\n{}",
+                    "Hide entries that are ignored by git",
+                    "Reads .gitignore, .git/info/exclude and the global excludes inside a git working tree and .ignore files everywhere"
+                ))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("group-dirs")
+                .long("group-dirs")
+                .help("Group directories before or after the other entries")
+                .value_name("WHEN")
+                .value_parser(["first", "last", "none"])
+                .action(ArgAction::Set),
+        )
         .arg(
             Arg::new("hidden")
                 .short('a')
@@ -394,36 +706,288 @@ fn sl() -> Command {
                 .help("Show hidden files")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("icons")
+                .long("icons")
+                .help("When to show icons in front of the names")
+                .long_help(format!(
+                    "{}\n{}\n{}",
+                    "When to show icons in front of the names [default: never]",
+                    "Auto shows icons only when writing to a terminal, the icons need a Nerd Font",
+                    "'--icons' without a value is the same as '--icons=auto'"
+                ))
+                .value_name("WHEN")
+                .value_parser(["auto", "always", "never"])
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("auto")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("ignore-glob")
+                .short('I')
+                .long("ignore-glob")
+                .help("Hide entries whose name matches the glob")
+                .long_help(format!(
+                    "{}\n{}\n{}",
+                    "Hide entries whose name matches the glob",
+                    "Several globs can be separated by '|' or given by repeating the flag, e.g. \"*.tmp|target\"",
+                    "Hidden directories are not descended into in tree or recursive mode"
+                ))
+                .value_name("GLOB")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print the entries as a JSON array")
+                .long_help(format!(
+                    "{}\n{}",
+                    "Print the entries as a JSON array",
+                    "Every entry contains [name, path, type, size, modified, accessed, created, changed, permissions, mode, owner, group, links, attributes, hidden, extension, symlink target]"
+                ))
+                .action(ArgAction::SetTrue)
+                .conflicts_with("ndjson"),
+        )
         .arg(
             Arg::new("long")
                 .short('l')
                 .long("long")
                 .help("Show more detailed entry information")
-                .long_help("Additionaly display [type, size, last modified, read_only]")
+                .long_help(format!(
+                    "{}\n{}",
+                    "Additionaly display [type, permissions, size, last modified]",
+                    "On unix also the link count, owner and group, the permissions are shown as rwxrwxrwx"
+                ))
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("larger-than")
+                .long("larger-than")
+                .help("Show only files larger than the size, e.g. 10M")
+                .long_help(format!(
+                    "{}\n{}",
+                    "Show only files larger than the size",
+                    "Sizes are given in bytes or with one of the units K, M, G, T like 500, 10K or 1.5M"
+                ))
+                .value_name("SIZE")
+                .value_parser(filter::parse_size)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("level")
+                .long("level")
+                .help("Limit the depth of a tree or recursive listing")
+                .long_help(format!(
+                    "{}\n{}",
+                    "Limit the depth of a tree or recursive listing",
+                    "A level of 1 only lists the entries of the given directory"
+                ))
+                .value_name("N")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("ndjson")
+                .long("ndjson")
+                .help("Print the entries as JSON, one object per line")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("no-ls-colors")
+                .long("no-ls-colors")
+                .help("Ignore the LS_COLORS environment variable")
+                .long_help(format!(
+                    "{}\n{}",
+                    "Ignore the LS_COLORS environment variable",
+                    "By default the file name colours are taken from LS_COLORS if it is set, otherwise from the theme"
+                ))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("only")
+                .long("only")
+                .help("Show only entries whose name matches the glob")
+                .long_help(format!(
+                    "{}\n{}\n{}",
+                    "Show only entries whose name matches the glob",
+                    "Several globs can be separated by '|' or given by repeating the flag",
+                    "Directories are still shown in tree mode to hold the structure"
+                ))
+                .value_name("GLOB")
+                .action(ArgAction::Append),
+        )
         .arg(
             Arg::new("override")
                 .short('o')
                 .long("override")
-                .help("Override all previously set flags")
+                .help("Ignore the config file and all previously set flags")
                 .long_help(format!(
                     "{}\n{}\n{}",
-                    "Override all previously set flags",
+                    "Ignore the config file and all previously set flags",
                     "This can be used when a custom alias for this command is set together with regularly used flags",
-                    "This flag allows to disable these flags and specify new ones"
+                    "Only the flags given after it are used"
+                ))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("recursive")
+                .short('R')
+                .long("recursive")
+                .help("List subdirectories recursively")
+                .long_help(format!(
+                    "{}\n{}",
+                    "List subdirectories recursively",
+                    "Every directory is listed in its own section, headed by its path"
                 ))
-                // TODO if new args -> add here to this list to override if needed
-                .overrides_with_all(["long", "files", "dirs", "hidden", "colour", "fullpath"])
+                .action(ArgAction::SetTrue)
+                .conflicts_with("tree"),
+        )
+        .arg(
+            Arg::new("regex")
+                .long("regex")
+                .help("Show only entries whose name matches the regex")
+                .value_name("REGEX")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("reverse")
+                .short('r')
+                .long("reverse")
+                .help("Reverse the sort order")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("si")
+                .long("si")
+                .help("Show sizes in powers of 1000 instead of 1024 in the long listing")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("smaller-than")
+                .long("smaller-than")
+                .help("Show only files smaller than the size, e.g. 1K")
+                .value_name("SIZE")
+                .value_parser(filter::parse_size)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("sort")
+                .short('s')
+                .long("sort")
+                .help("Sort the entries")
+                .long_help(format!(
+                    "{}\n{}\n{}",
+                    "Sort the entries [default: name]",
+                    "Names are compared case-insensitive and numbers by their value ('file2' before 'file10')",
+                    "Size and time put the biggest and newest entries first"
+                ))
+                .value_name("FIELD")
+                .value_parser(["name", "size", "time", "ext", "type", "none"])
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("system")
+                .long("system")
+                .help("Show entries with the windows system attribute")
+                .long_help(format!(
+                    "{}\n{}",
+                    "Show entries with the windows system attribute",
+                    "System files are hidden by default like in the windows explorer, this has no effect on other platforms"
+                ))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("theme")
+                .long("theme")
+                .help("Set the colour theme")
+                .long_help(format!(
+                    "{}\n{}\n{}",
+                    "Set the colour theme [default: iceberg-dark]",
+                    "Bundled themes are iceberg-dark and iceberg-light",
+                    "Otherwise the path to a theme file or the name of a file in the \"themes\" directory inside the config directory"
+                ))
+                .value_name("THEME")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("type")
+                .long("type")
+                .help("Show only entries of the types, f file, d directory, l symlink, x executable")
+                .value_name("TYPE")
+                .value_delimiter(',')
+                .value_parser(["f", "d", "l", "x"])
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("time")
+                .long("time")
+                .help("Choose the timestamp that is shown and used for sorting by time")
+                .long_help(format!(
+                    "{}\n{}",
+                    "Choose the timestamp that is shown and used for sorting by time [default: modified]",
+                    "changed is the last status change and only available on unix"
+                ))
+                .value_name("FIELD")
+                .value_parser(["modified", "created", "accessed", "changed"])
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("time-style")
+                .long("time-style")
+                .help("Format the timestamps in the long listing")
+                .long_help(format!(
+                    "{}\n{}\n{}",
+                    "Format the timestamps in the long listing [default: relative]",
+                    "relative like '3 mins ago', compact like '3m', iso, long-iso and full-iso like GNU ls",
+                    "+FORMAT uses a strftime format like '+%Y-%m-%d %H:%M'"
+                ))
+                .value_name("STYLE")
+                .value_parser(TimeStyle::parse)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("tree")
+                .short('T')
+                .long("tree")
+                .help("Show subdirectories as a tree")
                 .action(ArgAction::SetTrue),
         )
         .arg(
             Arg::new("path")
-                .help("Add a path to a directory")
-                .action(ArgAction::Set)
-                .num_args(1)
+                .help("Add paths to directories or files")
+                .long_help(format!(
+                    "{}\n{}\n{}",
+                    "Add paths to directories or files",
+                    "Files are listed first, every directory gets its own section",
+                    "Glob patterns like \"src/*.rs\" are expanded, if the shell doesn`t do it"
+                ))
+                .action(ArgAction::Append)
+                .num_args(1..)
                 .value_name("PATH"),
         )
+        .subcommand(
+            Command::new("config")
+                .about("Show the configuration")
+                .long_about(format!(
+                    "{}\n{}",
+                    "Show the configuration",
+                    "Default flags can be set in the file \"sl.toml\" inside the config directory"
+                ))
+                .arg(
+                    Arg::new("show")
+                        .long("show")
+                        .help("Show the effective configuration [default]")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("path")
+                        .long("path")
+                        .help("Show the path of the config file")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("show"),
+                ),
+        )
         .subcommand(
             Command::new("log")
                 .short_flag('L')
@@ -432,411 +996,715 @@ fn sl() -> Command {
         )
 }
 
-fn list_dirs(
-    path: PathBuf,
-    long_flag: bool,
-    hidden_flag: bool,
-    fullpath_flag: bool,
-    colour_flag: bool,
-    files_flag: bool,
-    dirs_flag: bool,
-) -> io::Result<()> {
-    if path.is_file() {
-        // return earlier
-        // TODO respect flags
-        println!("{}", path.display());
-        return Ok(());
-    } else if path.is_symlink() {
-        println!(
-            "{}",
-            path.read_link().expect("Unable to read symlink").display()
-        );
-        return Ok(());
+// expands glob patterns for shells that don`t do it themselves, like cmd.exe
+fn expand_path(arg: &str) -> Vec<PathBuf> {
+    if arg.is_empty() {
+        let current_dir = env::current_dir().unwrap_or_else(|err| {
+            error!("Unable to get current directory: {err}");
+            process::exit(1);
+        });
+        return vec![current_dir];
     }
 
-    let dir_entries = store_dir_entries(&path)?;
+    let path = PathBuf::from(arg);
+    if path.symlink_metadata().is_ok() || !arg.contains(['*', '?', '[']) {
+        return vec![path];
+    }
 
-    match long_flag {
-        true => {
-            for entry in dir_entries {
-                if entry.hidden && !hidden_flag {
-                    continue;
-                }
+    match glob::glob(arg) {
+        Ok(matches) => {
+            let found: Vec<PathBuf> = matches.filter_map(Result::ok).collect();
+            match found.is_empty() {
+                // report the pattern itself as not found
+                true => vec![path],
+                false => found,
+            }
+        }
+        Err(_) => vec![path],
+    }
+}
 
-                if files_flag && !entry.filetype.as_str().contains("file") {
-                    continue;
-                }
+fn list_paths(paths: Vec<PathBuf>, options: &Options) {
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            dirs.push(path);
+            continue;
+        }
 
-                if dirs_flag && !entry.filetype.as_str().contains("dir") {
-                    continue;
-                }
+        match store_entry(&path, options.dereference) {
+            Ok(mut entry) => {
+                // show files the way they were given
+                entry.name = path.to_string_lossy().to_string();
+                files.push(entry);
+            }
+            Err(err) => report_path_error(&path, &err),
+        }
+    }
+    sort_entries(&mut files, options);
 
-                let name_or_path = if fullpath_flag {
-                    entry.path
-                } else {
-                    entry.name
-                };
-
-                print_output_long(
-                    name_or_path,
-                    entry.filetype.as_str(),
-                    entry.filesize,
-                    colour_flag,
-                    entry.modified,
-                    entry.permissions,
-                    entry.file_extension,
-                );
+    if let Some(format) = options.export {
+        let mut storage = files;
+        for dir in dirs {
+            if let Err(err) = collect_entries(&dir, 1, options, &mut storage) {
+                report_path_error(&dir, &err);
             }
         }
-        false => {
-            for entry in dir_entries {
-                if entry.hidden && !hidden_flag {
-                    continue;
-                }
 
-                if files_flag && !entry.filetype.as_str().contains("file") {
-                    continue;
-                }
+        if let Err(err) = export::write_entries(&storage, format, &options.owners) {
+            error!("Unable to write the entries: {err}");
+            set_exit_status(2);
+        }
+        return;
+    }
 
-                if dirs_flag && !entry.filetype.as_str().contains("dir") {
-                    continue;
-                }
+    // tree and recursive listings show the path of a directory themselves
+    let headers = files.len() + dirs.len() > 1 && !options.tree && !options.recursive;
+    let has_files = !files.is_empty();
+    if has_files {
+        print_entries(files, options);
+    }
 
-                let name_or_path = if fullpath_flag {
-                    entry.path
-                } else {
-                    entry.name
-                };
-
-                print_output_short(
-                    name_or_path,
-                    entry.filetype.as_str(),
-                    entry.file_extension,
-                    colour_flag,
-                );
-            }
+    for (i, dir) in dirs.iter().enumerate() {
+        if i > 0 || has_files {
+            println!();
+        }
+        if headers {
+            println!("{}:", dir.display());
+        }
+
+        if let Err(err) = list_dirs(dir, options) {
+            report_path_error(dir, &err);
+        }
+    }
+}
+
+fn list_dirs(path: &PathBuf, options: &Options) -> io::Result<()> {
+    if options.tree {
+        println!("{}", path.display());
+        // the whole tree is gathered first, so the columns of the long listing line up
+        let mut rows = Vec::new();
+        list_tree(path, "", 1, options, &mut rows)?;
+        let columns = Columns::new(rows.iter().map(|(_, entry)| entry), options);
+        for (prefix, entry) in rows {
+            print_entry(entry, &prefix, options, &columns);
         }
+        return Ok(());
+    }
+
+    if options.recursive {
+        return list_recursive(path, 1, options);
     }
 
+    let mut dir_entries = store_dir_entries(path, options.dereference)?;
+    sort_entries(&mut dir_entries, options);
+    dir_entries.retain(|entry| !skip_entry(entry, options));
+    print_entries(dir_entries, options);
+
     Ok(())
 }
 
-fn store_dir_entries(entry_path: &PathBuf) -> io::Result<Vec<FileData>> {
-    let mut storage: Vec<FileData> = Vec::new();
-    for entry in fs::read_dir(entry_path)? {
-        let entry = entry?;
-
-        let path = entry.path().as_path().to_string_lossy().to_string();
-        let name = entry
-            .path()
-            .file_name()
-            .unwrap_or_else(|| {
-                error!("Unable to get the filename of {path}");
-                process::exit(1);
-            })
-            .to_string_lossy()
-            .to_string();
-        let hidden = is_hidden(&entry.path()).unwrap_or_else(|err| {
-            error!("Unable to tell if file {path} is hidden: {err}");
-            process::exit(1);
-        });
+fn list_tree(
+    path: &PathBuf,
+    prefix: &str,
+    depth: usize,
+    options: &Options,
+    rows: &mut Vec<(String, FileData)>,
+) -> io::Result<()> {
+    let mut dir_entries = store_dir_entries(path, options.dereference)?;
+    sort_entries(&mut dir_entries, options);
+    let dir_entries: Vec<FileData> = dir_entries
+        .into_iter()
+        .filter(|entry| {
+            // directories are needed to hold the structure of the tree,
+            // so only hide them when they are filtered out explicitly
+            if entry.is_dir()
+                && (options.files
+                    || options.name_filter.has_includes()
+                    || options.meta_filter.is_active())
+            {
+                !skip_subtree(entry, options)
+            } else {
+                !skip_entry(entry, options)
+            }
+        })
+        .collect();
 
-        let mut file_extension = String::new();
-        if let Some(extension) = entry.path().extension() {
-            file_extension.push_str(&extension.to_string_lossy().to_string());
+    let last = dir_entries.len().saturating_sub(1);
+    for (i, entry) in dir_entries.into_iter().enumerate() {
+        let (branch, indent) = if i == last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+
+        let subdir = descend_into(&entry, depth, options);
+        rows.push((format!("{prefix}{branch}"), entry));
+
+        if let Some(subdir) = subdir {
+            let prefix = format!("{prefix}{indent}");
+            if let Err(err) = list_tree(&subdir, &prefix, depth + 1, options, rows) {
+                report_unreadable(&subdir, &err);
+            }
         }
+    }
 
-        let metadata = fs::metadata(entry.path())?;
-        let filetype = metadata.file_type();
-        let filesize = metadata.file_size();
-        let permissions = metadata.permissions();
-        let modified_systime = metadata.modified()?;
-        let diff = SystemTime::now()
-            .duration_since(modified_systime)
-            .unwrap_or_else(|err| {
-                error!("Unable to get duration since the system is running: {err}");
-                process::exit(1);
-            })
-            .as_secs();
-        // TODO remove??
-        let modified = diff;
+    Ok(())
+}
 
-        let filedata = FileData::new(
-            name,
-            path,
-            filetype,
-            filesize,
-            hidden,
-            modified,
-            permissions,
-            file_extension,
-        );
-        storage.push(filedata);
+fn list_recursive(path: &PathBuf, depth: usize, options: &Options) -> io::Result<()> {
+    let mut dir_entries = store_dir_entries(path, options.dereference)?;
+    sort_entries(&mut dir_entries, options);
+
+    if depth > 1 {
+        println!();
     }
+    println!("{}:", path.display());
 
-    Ok(storage)
+    let subdirs: Vec<PathBuf> = dir_entries
+        .iter()
+        .filter_map(|entry| descend_into(entry, depth, options))
+        .collect();
+
+    dir_entries.retain(|entry| !skip_entry(entry, options));
+    print_entries(dir_entries, options);
+
+    for subdir in subdirs {
+        if let Err(err) = list_recursive(&subdir, depth + 1, options) {
+            report_unreadable(&subdir, &err);
+        }
+    }
+
+    Ok(())
 }
 
-fn print_output_short(name_or_path: String, filetype: &str, file_extension: String, colour: bool) {
-    if colour {
-        match filetype {
-            "file" => {
-                let mut name = String::new();
-                if NOTES_INFO
-                    .iter()
-                    .any(|it| name_or_path.to_lowercase().contains(it))
-                {
-                    let cstr = format!(
-                        "{}",
-                        name_or_path
-                            .on_truecolor(198, 200, 209)
-                            .truecolor(30, 33, 50)
-                    );
-                    name.push_str(&cstr);
-                } else if NOTES_IMPORTANT
-                    .iter()
-                    .any(|it| name_or_path.to_lowercase().contains(it))
-                {
-                    let cstr = format!(
-                        "{}",
-                        name_or_path
-                            .on_truecolor(226, 164, 120)
-                            .truecolor(30, 33, 50)
-                    );
-                    name.push_str(&cstr);
-                } else if NOTES_WARN
-                    .iter()
-                    .any(|it| name_or_path.to_lowercase().contains(it))
-                {
-                    let cstr = format!(
-                        "{}",
-                        name_or_path
-                            .on_truecolor(226, 120, 120)
-                            .truecolor(30, 33, 50)
-                    );
-                    name.push_str(&cstr);
-                } else if EXECUTABLE.iter().any(|it| &file_extension == it) {
-                    let cstr = format!("{}", name_or_path.bold().truecolor(226, 120, 120));
-                    name.push_str(&cstr);
-                } else if SPECIAL.iter().any(|it| &file_extension == it) {
-                    let cstr = format!("{}", name_or_path.truecolor(226, 164, 120));
-                    name.push_str(&cstr);
-                } else if PROGRAMMING.iter().any(|it| &file_extension == it) {
-                    let cstr = format!("{}", name_or_path.truecolor(180, 190, 130));
-                    name.push_str(&cstr);
-                } else if OFFICE.iter().any(|it| &file_extension == it) {
-                    let cstr = format!("{}", name_or_path.truecolor(226, 120, 120));
-                    name.push_str(&cstr);
-                } else if OTHER.iter().any(|it| &file_extension == it) {
-                    let cstr = format!("{}", name_or_path.truecolor(107, 112, 137));
-                    name.push_str(&cstr);
-                } else if MEDIA.iter().any(|it| &file_extension == it) {
-                    let cstr = format!("{}", name_or_path.truecolor(173, 160, 211));
-                    name.push_str(&cstr);
-                } else if ARCHIVES.iter().any(|it| &file_extension == it) {
-                    let cstr = format!("{}", name_or_path.truecolor(137, 184, 194));
-                    name.push_str(&cstr);
-                } else {
-                    let cstr = format!("{}", name_or_path.truecolor(198, 200, 209));
-                    name.push_str(&cstr);
-                }
-                println!("{}", name)
-            }
-            "dir" => {
-                println!("{}", name_or_path.truecolor(109, 144, 217).bold())
-            }
-            _ => {
-                println!("{}", name_or_path.italic().dimmed())
+// gathers the entries for machine-readable output instead of printing them
+fn collect_entries(
+    path: &PathBuf,
+    depth: usize,
+    options: &Options,
+    storage: &mut Vec<FileData>,
+) -> io::Result<()> {
+    let mut dir_entries = store_dir_entries(path, options.dereference)?;
+    sort_entries(&mut dir_entries, options);
+
+    for entry in dir_entries {
+        let subdir = match options.tree || options.recursive {
+            true => descend_into(&entry, depth, options),
+            false => None,
+        };
+
+        if !skip_entry(&entry, options) {
+            storage.push(entry);
+        }
+
+        if let Some(subdir) = subdir {
+            if let Err(err) = collect_entries(&subdir, depth + 1, options, storage) {
+                report_unreadable(&subdir, &err);
             }
         }
+    }
+
+    Ok(())
+}
+
+// returns the path of a directory entry that should be listed on the next level
+fn descend_into(entry: &FileData, depth: usize, options: &Options) -> Option<PathBuf> {
+    if !entry.is_dir() || skip_subtree(entry, options) {
+        return None;
+    }
+
+    if options.level.is_some_and(|level| depth >= level) {
+        return None;
+    }
+
+    // don`t follow symlinks to avoid endless loops
+    let path = PathBuf::from(&entry.path);
+    if entry.symlink_target.is_some() {
+        return None;
+    }
+
+    Some(path)
+}
+
+// true if the argument was given on the command line after the last --override
+fn given(matches: &ArgMatches, id: &str) -> bool {
+    if matches.value_source(id) != Some(ValueSource::CommandLine) {
+        return false;
+    }
+
+    // unset flags have a default value with an index as well
+    let override_index = matches
+        .indices_of("override")
+        .and_then(|indices| indices.max())
+        .filter(|_| matches.value_source("override") == Some(ValueSource::CommandLine));
+    match override_index {
+        Some(override_index) => matches
+            .indices_of(id)
+            .is_some_and(|mut indices| indices.any(|index| index > override_index)),
+        None => true,
+    }
+}
+
+fn sort_entries(entries: &mut [FileData], options: &Options) {
+    sort::sort_entries(
+        entries,
+        options.sort,
+        options.reverse,
+        options.group_dirs,
+        options.time_field,
+    );
+}
+
+fn skip_entry(entry: &FileData, options: &Options) -> bool {
+    if skip_subtree(entry, options) {
+        return true;
+    }
+
+    if options.files && !entry.is_file() {
+        return true;
+    }
+
+    if options.dirs && !entry.is_dir() {
+        return true;
+    }
+
+    if !options.name_filter.is_included(&entry.name) || !options.meta_filter.is_included(entry) {
+        return true;
+    }
+
+    false
+}
+
+// filters that hide a directory together with its content
+fn skip_subtree(entry: &FileData, options: &Options) -> bool {
+    (entry.hidden && !options.hidden)
+        || (entry.system && !options.system)
+        || options.name_filter.is_excluded(&entry.name)
+        || is_git_ignored(entry, options)
+}
+
+fn is_git_ignored(entry: &FileData, options: &Options) -> bool {
+    options
+        .git_ignore
+        .as_ref()
+        .is_some_and(|git_ignore| git_ignore.is_ignored(Path::new(&entry.path), entry.is_dir()))
+}
+
+fn print_entries(entries: Vec<FileData>, options: &Options) {
+    if options.grid && !options.long {
+        let cells: Vec<String> = entries
+            .iter()
+            .map(|entry| format_output_short(name_or_path(entry, options), entry, options))
+            .collect();
+        grid::print_grid(&cells, options.across);
+        return;
+    }
+
+    let columns = Columns::new(&entries, options);
+    for entry in entries {
+        print_entry(entry, "", options, &columns);
+    }
+}
+
+fn name_or_path(entry: &FileData, options: &Options) -> String {
+    if options.fullpath {
+        entry.path.clone()
     } else {
-        match filetype {
-            "file" => {
-                println!("{}", name_or_path)
-            }
-            "dir" => {
-                println!("{}", name_or_path.bold())
+        entry.name.clone()
+    }
+}
+
+fn print_entry(entry: FileData, prefix: &str, options: &Options, columns: &Columns) {
+    let name_or_path = name_or_path(&entry, options);
+
+    match options.long {
+        true => {
+            print_output_long(prefix, name_or_path, &entry, options, columns);
+        }
+        false => {
+            print_output_short(prefix, name_or_path, &entry, options);
+        }
+    }
+}
+
+// widths of the long listing columns, taken from all entries of a listing like ls
+#[derive(Default)]
+struct Columns {
+    links: usize,
+    owner: usize,
+    group: usize,
+    size: usize,
+    time: usize,
+}
+
+impl Columns {
+    fn new<'a>(entries: impl IntoIterator<Item = &'a FileData>, options: &Options) -> Columns {
+        let mut columns = Columns {
+            size: options.size_style.width().0,
+            time: options.time_style.width(),
+            ..Columns::default()
+        };
+        if !options.long {
+            return columns;
+        }
+
+        for entry in entries {
+            let (links, owner, group) = ownership(entry, options);
+            columns.links = columns.links.max(links.chars().count());
+            columns.owner = columns.owner.max(owner.chars().count());
+            columns.group = columns.group.max(group.chars().count());
+            columns.size = columns
+                .size
+                .max(format_size(entry, options).0.chars().count());
+            columns.time = columns
+                .time
+                .max(format_time(entry, options).chars().count());
+        }
+
+        columns
+    }
+}
+
+// link count, owner and group, '?' if the metadata could not be read
+fn ownership(entry: &FileData, options: &Options) -> (String, String, String) {
+    match entry.unix {
+        Some(unix) => (
+            unix.nlink.to_string(),
+            options.owners.user_name(unix.uid),
+            options.owners.group_name(unix.gid),
+        ),
+        None => ("?".to_string(), "?".to_string(), "?".to_string()),
+    }
+}
+
+fn format_time(entry: &FileData, options: &Options) -> String {
+    options.time_style.format(entry.time(options.time_field))
+}
+
+fn format_size(entry: &FileData, options: &Options) -> (String, String) {
+    match entry.unreadable {
+        true => (String::new(), "?".to_string()),
+        false => options.size_style.format(entry.filesize, entry.is_file()),
+    }
+}
+
+fn store_dir_entries(entry_path: &PathBuf, dereference: bool) -> io::Result<Vec<FileData>> {
+    let mut storage: Vec<FileData> = Vec::new();
+    for entry in fs::read_dir(entry_path)? {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                report_unreadable(entry_path, &err);
+                continue;
             }
-            _ => {
-                println!("{}", name_or_path.italic().dimmed())
+        };
+
+        match store_entry(&entry.path(), dereference) {
+            Ok(filedata) => storage.push(filedata),
+            Err(err) => {
+                // keep the entry in the listing, only without its metadata
+                report_unreadable(&entry.path(), &err);
+                let (name, path, file_extension) = entry_names(&entry.path());
+                storage.push(FileData::unreadable(
+                    name,
+                    path,
+                    entry.file_type().ok(),
+                    file_extension,
+                ));
             }
         }
     }
+
+    Ok(storage)
 }
 
-fn print_output_long(
-    name_or_path: String,
-    filetype: &str,
-    mut filesize: Vec<String>,
-    colour: bool,
-    modified: String,
-    permissions: Perms,
-    file_extension: String,
-) {
-    let mut ftype = String::new();
-    let mut name = String::new();
-    match filetype {
-        "file" => {
-            ftype.push_str(".");
-            if colour {
-                if NOTES_INFO
-                    .iter()
-                    .any(|it| name_or_path.to_lowercase().contains(it))
-                {
-                    let cstr = format!(
-                        "{}",
-                        name_or_path
-                            .on_truecolor(198, 200, 209)
-                            .truecolor(30, 33, 50)
-                    );
-                    name.push_str(&cstr);
-                } else if NOTES_IMPORTANT
-                    .iter()
-                    .any(|it| name_or_path.to_lowercase().contains(it))
-                {
-                    let cstr = format!(
-                        "{}",
-                        name_or_path
-                            .on_truecolor(226, 164, 120)
-                            .truecolor(30, 33, 50)
-                    );
-                    name.push_str(&cstr);
-                } else if NOTES_WARN
-                    .iter()
-                    .any(|it| name_or_path.to_lowercase().contains(it))
-                {
-                    let cstr = format!(
-                        "{}",
-                        name_or_path
-                            .on_truecolor(226, 120, 120)
-                            .truecolor(30, 33, 50)
-                    );
-                    name.push_str(&cstr);
-                } else if EXECUTABLE.iter().any(|it| &file_extension == it) {
-                    let cstr = format!("{}", name_or_path.bold().truecolor(226, 120, 120));
-                    name.push_str(&cstr);
-                } else if SPECIAL.iter().any(|it| &file_extension == it) {
-                    let cstr = format!(
-                        "{}",
-                        name_or_path
-                            .truecolor(226, 164, 120)
-                            .on_truecolor(22, 24, 33)
-                    );
-                    name.push_str(&cstr);
-                } else if PROGRAMMING.iter().any(|it| &file_extension == it) {
-                    let cstr = format!("{}", name_or_path.truecolor(180, 190, 130));
-                    name.push_str(&cstr);
-                } else if OFFICE.iter().any(|it| &file_extension == it) {
-                    let cstr = format!("{}", name_or_path.truecolor(226, 120, 120));
-                    name.push_str(&cstr);
-                } else if OTHER.iter().any(|it| &file_extension == it) {
-                    let cstr = format!("{}", name_or_path.truecolor(107, 112, 137));
-                    name.push_str(&cstr);
-                } else if MEDIA.iter().any(|it| &file_extension == it) {
-                    let cstr = format!("{}", name_or_path.truecolor(173, 160, 211));
-                    name.push_str(&cstr);
-                } else if ARCHIVES.iter().any(|it| &file_extension == it) {
-                    let cstr = format!("{}", name_or_path.truecolor(137, 184, 194));
-                    name.push_str(&cstr);
-                } else {
-                    let cstr = format!("{}", name_or_path.truecolor(198, 200, 209));
-                    name.push_str(&cstr);
+fn store_entry(entry_path: &Path, dereference: bool) -> io::Result<FileData> {
+    let (name, path, file_extension) = entry_names(entry_path);
+
+    let mut metadata = fs::symlink_metadata(entry_path)?;
+    let mut symlink_target = None;
+    let mut broken_link = false;
+    if metadata.file_type().is_symlink() {
+        symlink_target = fs::read_link(entry_path).ok();
+
+        match fs::metadata(entry_path) {
+            Ok(target_metadata) => {
+                if dereference {
+                    metadata = target_metadata;
                 }
-            } else {
-                name.push_str(&name_or_path);
             }
+            Err(_) => broken_link = true,
         }
-        "dir" => {
-            ftype.push_str("d");
-            if colour {
-                let cstr = format!("{}", name_or_path.truecolor(109, 144, 217).bold());
-                name.push_str(&cstr);
-            } else {
-                let cstr = format!("{}", name_or_path.bold());
-                name.push_str(&cstr);
-            }
+    }
+    let hidden = platform::is_hidden(entry_path, &metadata);
+
+    let mut filedata = FileData::new(name, path, &metadata, hidden, file_extension);
+    filedata.symlink_target = symlink_target;
+    filedata.broken_link = broken_link;
+
+    Ok(filedata)
+}
+
+// returns the name, the path and the extension of an entry
+fn entry_names(entry_path: &Path) -> (String, String, String) {
+    let path = entry_path.to_string_lossy().to_string();
+    // paths like '..' have no filename, show them as they are
+    let name = entry_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.clone());
+    let mut file_extension = String::new();
+    if let Some(extension) = entry_path.extension() {
+        file_extension.push_str(&extension.to_string_lossy());
+    }
+
+    (name, path, file_extension)
+}
+
+fn report_unreadable(path: &Path, err: &io::Error) {
+    warn!("Unable to read \'{}\': {}", path.display(), err);
+    set_exit_status(1);
+}
+
+fn report_path_error(path: &Path, err: &io::Error) {
+    match err.kind() {
+        io::ErrorKind::NotFound => {
+            info!("\'{}\' not found: {}", path.display(), err);
+        }
+        io::ErrorKind::PermissionDenied => {
+            warn!("Permission denied for \'{}\': {}", path.display(), err);
         }
         _ => {
-            ftype.push_str("s");
-            let cstr = format!("{}", name_or_path.italic().dimmed());
-            name.push_str(&cstr);
+            error!(
+                "Unable to get the entries of the directory \'{}\': {}",
+                path.display(),
+                err
+            );
         }
     }
+    set_exit_status(2);
+}
+
+fn set_exit_status(status: i32) {
+    EXIT_STATUS.fetch_max(status, Ordering::Relaxed);
+}
 
-    let mut perm_read = String::new();
-    let mut perm_write = String::new();
-    if colour && permissions.write.contains("-") {
-        perm_read.push_str(&format!("{}", permissions.read.truecolor(250, 0, 104)));
-        perm_write.push_str(&format!("{}", permissions.write.truecolor(250, 0, 104)));
+fn filetype_name(filetype: Option<FileType>) -> &'static str {
+    let Some(filetype) = filetype else {
+        return "unknown";
+    };
+
+    if filetype.is_file() {
+        "file"
+    } else if filetype.is_dir() {
+        "dir"
+    } else if filetype.is_symlink() {
+        "symlink"
     } else {
-        perm_read.push_str(&permissions.read);
-        perm_write.push_str(&permissions.write);
+        "other"
     }
+}
 
-    let mut fsize_unit = String::new();
-    match colour {
-        true => {
-            if let Some(f) = filesize.pop() {
-                fsize_unit.push_str(&f.truecolor(50, 170, 130).to_string())
-            } else {
-                fsize_unit.push_str(&"".truecolor(198, 200, 209).to_string())
-            }
-        }
-        false => {
-            if let Some(f) = filesize.pop() {
-                fsize_unit.push_str(&f.truecolor(198, 200, 209).to_string())
-            } else {
-                fsize_unit.push_str(&"".truecolor(198, 200, 209).to_string())
-            }
+// the full mode on unix, otherwise only whether the entry is writable
+fn format_permissions(entry: &FileData, octal: bool) -> String {
+    if let Some(unix) = entry.unix {
+        return match octal {
+            true => format!("{:04o}", unix.mode & 0o7777),
+            false => format_mode(unix.mode),
+        };
+    }
+
+    // unreadable entries keep the width of the column
+    match &entry.permissions {
+        Some(permissions) if permissions.readonly() => String::from("r-"),
+        Some(_) => String::from("rw"),
+        None if cfg!(unix) && octal => String::from("????"),
+        None if cfg!(unix) => String::from("?????????"),
+        None => String::from("??"),
+    }
+}
+
+// rwxrwxrwx like ls, setuid, setgid and sticky replace the execute bits
+fn format_mode(mode: u32) -> String {
+    let bit = |mask: u32, c: char| match mode & mask != 0 {
+        true => c,
+        false => '-',
+    };
+    // lowercase if the execute bit is set as well, uppercase otherwise
+    let special = |exec: u32, special: u32, c: char| match (mode & exec != 0, mode & special != 0) {
+        (true, true) => c,
+        (false, true) => c.to_ascii_uppercase(),
+        (true, false) => 'x',
+        (false, false) => '-',
+    };
+
+    [
+        bit(0o400, 'r'),
+        bit(0o200, 'w'),
+        special(0o100, 0o4000, 's'),
+        bit(0o40, 'r'),
+        bit(0o20, 'w'),
+        special(0o10, 0o2000, 's'),
+        bit(0o4, 'r'),
+        bit(0o2, 'w'),
+        special(0o1, 0o1000, 't'),
+    ]
+    .iter()
+    .collect()
+}
+
+fn print_output_short(prefix: &str, name_or_path: String, entry: &FileData, options: &Options) {
+    println!(
+        "{}{}",
+        prefix,
+        format_output_short(name_or_path, entry, options)
+    );
+}
+
+fn format_output_short(name_or_path: String, entry: &FileData, options: &Options) -> String {
+    // the icon takes the colour of the name
+    let name_or_path = match &options.icons {
+        Some(icons) => format!(
+            "{} {}",
+            icons.icon(entry, &options.categories),
+            name_or_path
+        ),
+        None => name_or_path,
+    };
+    let filetype = filetype_name(entry.filetype);
+    let theme = &options.theme;
+
+    if let Some(ls_colors) = options.ls_colors.as_ref().filter(|_| options.colour) {
+        ls_colors.paint(&name_or_path, entry)
+    } else if options.colour {
+        match filetype {
+            "file" => category::style(&options.categories, &entry.name, &entry.file_extension)
+                .unwrap_or(&theme.file)
+                .paint(&name_or_path, options.depth),
+            "dir" => theme.dir.paint(&name_or_path, options.depth),
+            _ if entry.broken_link => theme.broken_link.paint(&name_or_path, options.depth),
+            _ => theme.symlink.paint(&name_or_path, options.depth),
         }
+    } else {
+        name_or_path
+    }
+}
+
+fn print_output_long(
+    prefix: &str,
+    name_or_path: String,
+    entry: &FileData,
+    options: &Options,
+    columns: &Columns,
+) {
+    let colour = options.colour;
+    let theme = &options.theme;
+    let filetype = filetype_name(entry.filetype);
+    let (filesize, filesize_unit) = format_size(entry, options);
+    let modified = format_time(entry, options);
+    let permissions = format_permissions(entry, options.octal);
+
+    // the type letters of ls, except '.' for files
+    let ftype = match filetype {
+        "file" => '.',
+        "dir" => 'd',
+        "symlink" => 'l',
+        "unknown" => '?',
+        _ => match platform::special_file_key(entry.filetype) {
+            Some("pi") => 'p',
+            Some("so") => 's',
+            Some("bd") => 'b',
+            Some("cd") => 'c',
+            _ => '?',
+        },
     };
 
-    let mut fsize = String::new();
-    match colour {
+    let mut name = format_output_short(name_or_path, entry, options);
+    if let Some(target) = entry.symlink_target.as_ref().filter(|_| entry.is_symlink()) {
+        let target = target.display().to_string();
+        let target = match (colour && entry.broken_link, &options.ls_colors) {
+            (true, Some(ls_colors)) => ls_colors.paint_missing(&target),
+            (true, None) => theme.broken_link.paint(&target, options.depth),
+            (false, _) => target,
+        };
+        let cstr = format!(" -> {}", target);
+        name.push_str(&cstr);
+    }
+
+    let readonly = entry
+        .permissions
+        .as_ref()
+        .is_some_and(|permissions| permissions.readonly());
+    let permissions = match colour && readonly {
+        true => theme.readonly.paint(&permissions, options.depth),
+        false => permissions,
+    };
+
+    // link count, owner and group like ls, only on unix
+    let ownership = match cfg!(unix) {
         true => {
-            if let Some(f) = filesize.pop() {
-                fsize.push_str(&f.truecolor(102, 255, 179).to_string())
-            } else {
-                fsize.push_str(&"".truecolor(198, 200, 209).to_string())
-            }
-        }
-        false => {
-            if let Some(f) = filesize.pop() {
-                fsize.push_str(&f.truecolor(198, 200, 209).to_string())
-            } else {
-                fsize.push_str(&"".truecolor(198, 200, 209).to_string())
-            }
+            let (links, owner, group) = ownership(entry, options);
+            format!(
+                " {:>links_width$} {:<owner_width$} {:<group_width$}",
+                links,
+                owner,
+                group,
+                links_width = columns.links,
+                owner_width = columns.owner,
+                group_width = columns.group,
+            )
         }
+        false => String::new(),
+    };
+
+    // the attribute column, only on windows
+    let attributes = match entry.attributes {
+        Some(attributes) => format!(" {}", platform::format_attributes(attributes)),
+        None if cfg!(windows) => String::from(" ????????"),
+        None => String::new(),
+    };
+
+    // pad before colouring, escape sequences would count as width otherwise
+    let unit_width = options.size_style.width().1;
+    let filesize = format!("{:>width$}", filesize, width = columns.size);
+    let filesize_unit = format!("{:<unit_width$}", filesize_unit);
+    let modified = format!("{:>width$}", modified, width = columns.time);
+
+    let (fsize, fsize_unit, modified) = match colour {
+        true => (
+            theme.size.paint(&filesize, options.depth),
+            theme.unit.paint(&filesize_unit, options.depth),
+            theme.date.paint(&modified, options.depth),
+        ),
+        false => (filesize, filesize_unit, modified),
     };
 
-    let modified = match colour {
-        true => modified.truecolor(97, 88, 111),
-        false => modified.truecolor(198, 200, 209),
+    // entries outside of a working tree get an empty column
+    let git_status = match &options.git {
+        Some(git) => match git.status(Path::new(&entry.path)) {
+            Some(status) => format!("{}  ", status.paint(theme, colour, options.depth)),
+            None => "    ".to_string(),
+        },
+        None => String::new(),
     };
 
     println!(
-        "{}{}{}\t{:>30}{}  {:>37}  {}",
+        "{}{}{}{} {}{}  {}  {}{}{}",
         ftype,
-        perm_read,
-        perm_write,
+        permissions,
+        ownership,
+        attributes,
         fsize,
         fsize_unit,
-        modified.to_string(),
+        modified,
+        git_status,
+        prefix,
         name,
     );
 }
 
-fn is_hidden(file_path: &PathBuf) -> std::io::Result<bool> {
-    let metadata = fs::metadata(file_path)?;
-    let attributes = metadata.file_attributes();
-
-    if (attributes & 0x2) > 0 {
-        Ok(true)
-    } else {
-        Ok(false)
-    }
-}
-
 fn check_create_config_dir() -> io::Result<PathBuf> {
     let mut new_dir = PathBuf::new();
     match dirs::config_dir() {
@@ -855,23 +1723,27 @@ fn check_create_config_dir() -> io::Result<PathBuf> {
     Ok(new_dir)
 }
 
-fn show_log_file(config_dir: &PathBuf) -> io::Result<String> {
+fn show_log_file(config_dir: &PathBuf, colour: bool) -> io::Result<String> {
     let log_path = Path::new(&config_dir).join("sl.log");
     match log_path.try_exists()? {
-        true => {
-            return Ok(format!(
-                "{} {}\n{}",
-                "Log location:".italic().dimmed(),
-                &log_path.display(),
-                fs::read_to_string(&log_path)?
-            ));
-        }
-        false => {
-            return Ok(format!(
-                "{} {}",
-                "No log file found:".red().bold().to_string(),
-                log_path.display()
-            ))
-        }
+        true => Ok(format!(
+            "{} {}\n{}",
+            styled("Log location:", OwoStyle::new().italic().dimmed(), colour),
+            &log_path.display(),
+            fs::read_to_string(&log_path)?
+        )),
+        false => Ok(format!(
+            "{} {}",
+            styled("No log file found:", OwoStyle::new().red().bold(), colour),
+            log_path.display()
+        )),
+    }
+}
+
+// labels of the subcommands, plain if colours are turned off
+fn styled(text: &str, style: OwoStyle, colour: bool) -> String {
+    match colour {
+        true => text.style(style).to_string(),
+        false => text.to_string(),
     }
 }
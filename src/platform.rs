@@ -0,0 +1,216 @@
+// platform specific metadata handling
+use std::{
+    fs::{FileType, Metadata, Permissions},
+    path::Path,
+    time::SystemTime,
+};
+
+#[cfg(windows)]
+use std::os::windows::fs::MetadataExt;
+
+#[cfg(unix)]
+use std::os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt};
+
+#[cfg(unix)]
+use uzers::{Groups, Users, UsersCache};
+
+// FILE_ATTRIBUTE_* bits of the windows file attributes
+const READONLY: u32 = 0x1;
+const HIDDEN: u32 = 0x2;
+const SYSTEM: u32 = 0x4;
+const ARCHIVE: u32 = 0x20;
+const REPARSE_POINT: u32 = 0x400;
+const COMPRESSED: u32 = 0x800;
+const OFFLINE: u32 = 0x1000;
+const ENCRYPTED: u32 = 0x4000;
+
+// the letters of the attribute column in long mode, '-' if the bit is not set
+const ATTRIBUTE_LETTERS: [(u32, char); 8] = [
+    (READONLY, 'R'),
+    (HIDDEN, 'H'),
+    (SYSTEM, 'S'),
+    (ARCHIVE, 'A'),
+    (COMPRESSED, 'C'),
+    (ENCRYPTED, 'E'),
+    (REPARSE_POINT, 'L'),
+    (OFFLINE, 'O'),
+];
+
+#[cfg(windows)]
+pub fn is_hidden(_path: &Path, metadata: &Metadata) -> bool {
+    (metadata.file_attributes() & HIDDEN) > 0
+}
+
+#[cfg(unix)]
+pub fn is_hidden(path: &Path, _metadata: &Metadata) -> bool {
+    // dotfiles are hidden on unix
+    path.file_name()
+        .map(|name| name.to_string_lossy().starts_with('.'))
+        .unwrap_or(false)
+}
+
+#[cfg(not(any(windows, unix)))]
+pub fn is_hidden(path: &Path, _metadata: &Metadata) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy().starts_with('.'))
+        .unwrap_or(false)
+}
+
+// the last status change of the inode
+#[cfg(unix)]
+pub fn changed(metadata: &Metadata) -> Option<SystemTime> {
+    let secs = u64::try_from(metadata.ctime()).ok()?;
+    let nanos = u32::try_from(metadata.ctime_nsec()).ok()?;
+    SystemTime::UNIX_EPOCH.checked_add(std::time::Duration::new(secs, nanos))
+}
+
+#[cfg(not(unix))]
+pub fn changed(_metadata: &Metadata) -> Option<SystemTime> {
+    None
+}
+
+#[cfg(windows)]
+pub fn attributes(metadata: &Metadata) -> Option<u32> {
+    Some(metadata.file_attributes())
+}
+
+// only windows has file attributes
+#[cfg(not(windows))]
+pub fn attributes(_metadata: &Metadata) -> Option<u32> {
+    None
+}
+
+pub fn is_system(attributes: Option<u32>) -> bool {
+    attributes.is_some_and(|attributes| attributes & SYSTEM > 0)
+}
+
+pub fn format_attributes(attributes: u32) -> String {
+    ATTRIBUTE_LETTERS
+        .iter()
+        .map(|(bit, letter)| match attributes & bit > 0 {
+            true => *letter,
+            false => '-',
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+pub fn file_size(metadata: &Metadata) -> u64 {
+    metadata.file_size()
+}
+
+#[cfg(unix)]
+pub fn file_size(metadata: &Metadata) -> u64 {
+    metadata.size()
+}
+
+#[cfg(not(any(windows, unix)))]
+pub fn file_size(metadata: &Metadata) -> u64 {
+    metadata.len()
+}
+
+#[cfg(unix)]
+pub fn is_executable(permissions: &Permissions) -> bool {
+    permissions.mode() & 0o111 != 0
+}
+
+// there is no executable bit outside of unix
+#[cfg(not(unix))]
+pub fn is_executable(_permissions: &Permissions) -> bool {
+    false
+}
+
+// windows decides by the extension instead
+#[cfg(windows)]
+pub fn has_executable_extension(extension: &str) -> bool {
+    ["exe", "bat", "cmd", "com", "msi", "ps1"].contains(&extension.to_lowercase().as_str())
+}
+
+#[cfg(not(windows))]
+pub fn has_executable_extension(_extension: &str) -> bool {
+    false
+}
+
+// the LS_COLORS key for files that are neither regular files, dirs nor symlinks
+#[cfg(unix)]
+pub fn special_file_key(filetype: Option<FileType>) -> Option<&'static str> {
+    let filetype = filetype?;
+    if filetype.is_fifo() {
+        Some("pi")
+    } else if filetype.is_socket() {
+        Some("so")
+    } else if filetype.is_block_device() {
+        Some("bd")
+    } else if filetype.is_char_device() {
+        Some("cd")
+    } else {
+        None
+    }
+}
+
+#[cfg(not(unix))]
+pub fn special_file_key(_filetype: Option<FileType>) -> Option<&'static str> {
+    None
+}
+
+// mode, ownership and link count, only available on unix
+#[derive(Clone, Copy)]
+pub struct UnixMeta {
+    pub mode: u32,
+    pub uid: u32,
+    pub gid: u32,
+    pub nlink: u64,
+}
+
+#[cfg(unix)]
+pub fn unix_meta(metadata: &Metadata) -> Option<UnixMeta> {
+    Some(UnixMeta {
+        mode: metadata.mode(),
+        uid: metadata.uid(),
+        gid: metadata.gid(),
+        nlink: metadata.nlink(),
+    })
+}
+
+#[cfg(not(unix))]
+pub fn unix_meta(_metadata: &Metadata) -> Option<UnixMeta> {
+    None
+}
+
+// user and group names, looked up once per id
+#[cfg(unix)]
+#[derive(Default)]
+pub struct Owners(UsersCache);
+
+#[cfg(unix)]
+impl Owners {
+    // ids without a name are shown as numbers like ls does
+    pub fn user_name(&self, uid: u32) -> String {
+        self.0
+            .get_user_by_uid(uid)
+            .map(|user| user.name().to_string_lossy().to_string())
+            .unwrap_or_else(|| uid.to_string())
+    }
+
+    pub fn group_name(&self, gid: u32) -> String {
+        self.0
+            .get_group_by_gid(gid)
+            .map(|group| group.name().to_string_lossy().to_string())
+            .unwrap_or_else(|| gid.to_string())
+    }
+}
+
+#[cfg(not(unix))]
+#[derive(Default)]
+pub struct Owners;
+
+#[cfg(not(unix))]
+impl Owners {
+    pub fn user_name(&self, uid: u32) -> String {
+        uid.to_string()
+    }
+
+    pub fn group_name(&self, gid: u32) -> String {
+        gid.to_string()
+    }
+}
@@ -0,0 +1,231 @@
+// how file sizes are shown in the long listing
+use serde::{Deserialize, Serialize};
+
+pub const KB: u64 = 1024;
+pub const MB: u64 = 1024_u64.pow(2);
+pub const GB: u64 = 1024_u64.pow(3);
+pub const TB: u64 = 1024_u64.pow(4);
+
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum SizeStyle {
+    // 1024-based with one letter, like '1.2K'
+    Short,
+    // 1000-based, like '1.2k'
+    Si,
+    // 1024-based with IEC units, like '1.2KiB'
+    Binary,
+    // the exact number of bytes, like '1,234,567B'
+    Bytes,
+    // the size in blocks rounded up like GNU ls, the unit is only shown without a number like 'M'
+    Block { size: u64, suffix: String },
+}
+
+impl SizeStyle {
+    // used as clap value parser for --block-size and for the config file
+    pub fn parse(arg: &str) -> Result<SizeStyle, String> {
+        match arg {
+            "short" => Ok(SizeStyle::Short),
+            "si" => Ok(SizeStyle::Si),
+            "binary" => Ok(SizeStyle::Binary),
+            "bytes" => Ok(SizeStyle::Bytes),
+            _ => parse_block_size(arg),
+        }
+    }
+
+    // the widths of the size and the unit, so the columns stay aligned
+    pub fn width(&self) -> (usize, usize) {
+        match self {
+            SizeStyle::Short | SizeStyle::Si => (6, 1),
+            SizeStyle::Binary => (6, 3),
+            SizeStyle::Bytes => (13, 1),
+            SizeStyle::Block { suffix, .. } => (6, suffix.len()),
+        }
+    }
+
+    // returns the size and its unit, e.g. ("1.2", "K"),
+    // only empty files are shown as '0B', other entries without a size as '-'
+    pub fn format(&self, filesize: u64, is_file: bool) -> (String, String) {
+        if filesize == 0 && !is_file {
+            return (String::new(), "-".to_string());
+        }
+
+        match self {
+            SizeStyle::Short => scale(filesize, KB, ["B", "K", "M", "G", "T"]),
+            SizeStyle::Si => scale(filesize, 1000, ["B", "k", "M", "G", "T"]),
+            SizeStyle::Binary => scale(filesize, KB, ["B", "KiB", "MiB", "GiB", "TiB"]),
+            SizeStyle::Bytes => (thousands(filesize), "B".to_string()),
+            SizeStyle::Block { size, suffix } => {
+                (filesize.div_ceil(*size).to_string(), suffix.clone())
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for SizeStyle {
+    type Error = String;
+
+    fn try_from(style: String) -> Result<Self, Self::Error> {
+        SizeStyle::parse(&style)
+    }
+}
+
+impl From<SizeStyle> for String {
+    fn from(style: SizeStyle) -> Self {
+        match style {
+            SizeStyle::Short => String::from("short"),
+            SizeStyle::Si => String::from("si"),
+            SizeStyle::Binary => String::from("binary"),
+            SizeStyle::Bytes => String::from("bytes"),
+            SizeStyle::Block { size, suffix } if suffix.is_empty() => size.to_string(),
+            SizeStyle::Block { suffix, .. } => suffix,
+        }
+    }
+}
+
+fn scale(filesize: u64, base: u64, units: [&str; 5]) -> (String, String) {
+    let mut unit = 1;
+    let mut index = 0;
+    while index < units.len() - 1 && filesize >= unit * base {
+        unit *= base;
+        index += 1;
+    }
+
+    let size = match index {
+        0 => filesize.to_string(),
+        _ => (((filesize as f64 / unit as f64) * 10.0).round() / 10.0).to_string(),
+    };
+
+    (size, units[index].to_string())
+}
+
+// 1234567 -> '1,234,567'
+fn thousands(number: u64) -> String {
+    let digits = number.to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+// block sizes like GNU ls: '512', '4K', 'M', 'KB' (1000-based) or 'MiB' (1024-based)
+fn parse_block_size(arg: &str) -> Result<SizeStyle, String> {
+    if arg.is_empty() {
+        return Err(String::from("invalid block size \'\'"));
+    }
+
+    let split = arg.find(|c: char| !c.is_ascii_digit()).unwrap_or(arg.len());
+    let (number, unit) = arg.split_at(split);
+
+    let factor = match unit {
+        "" => 1,
+        "K" | "k" | "KiB" => KB,
+        "M" | "MiB" => MB,
+        "G" | "GiB" => GB,
+        "T" | "TiB" => TB,
+        "kB" | "KB" => 1000,
+        "MB" => 1000_u64.pow(2),
+        "GB" => 1000_u64.pow(3),
+        "TB" => 1000_u64.pow(4),
+        _ => {
+            return Err(format!(
+                "invalid block size \'{arg}\', expected a size like 512, 4K, M, KB or MiB"
+            ))
+        }
+    };
+
+    // without a number the unit is shown behind the sizes, like GNU ls
+    let (count, suffix) = match number {
+        "" => (1, unit.to_string()),
+        _ => (
+            number
+                .parse::<u64>()
+                .map_err(|_| format!("invalid block size \'{arg}\'"))?,
+            String::new(),
+        ),
+    };
+
+    match count.checked_mul(factor) {
+        Some(size) if size > 0 => Ok(SizeStyle::Block { size, suffix }),
+        _ => Err(format!("invalid block size \'{arg}\'")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(style: &str, filesize: u64) -> String {
+        let (size, unit) = SizeStyle::parse(style).unwrap().format(filesize, true);
+        format!("{size}{unit}")
+    }
+
+    #[test]
+    fn thousands_separators() {
+        assert_eq!(thousands(0), "0");
+        assert_eq!(thousands(999), "999");
+        assert_eq!(thousands(1000), "1,000");
+        assert_eq!(thousands(999_999), "999,999");
+        assert_eq!(thousands(1_000_000), "1,000,000");
+        assert_eq!(thousands(u64::MAX), "18,446,744,073,709,551,615");
+    }
+
+    #[test]
+    fn scaled_units() {
+        assert_eq!(format("short", 1023), "1023B");
+        assert_eq!(format("short", 1024), "1K");
+        assert_eq!(format("short", 1536), "1.5K");
+        assert_eq!(format("si", 999), "999B");
+        assert_eq!(format("si", 1000), "1k");
+        assert_eq!(format("si", 1_500_000), "1.5M");
+        assert_eq!(format("binary", 1024 * 1024), "1MiB");
+        // the largest unit is not exceeded
+        assert_eq!(format("short", 2048 * TB), "2048T");
+        assert_eq!(format("short", u64::MAX), "16777216T");
+    }
+
+    #[test]
+    fn empty_entries() {
+        assert_eq!(format("short", 0), "0B");
+        assert_eq!(format("bytes", 0), "0B");
+        assert_eq!(
+            SizeStyle::Short.format(0, false),
+            (String::new(), "-".to_string())
+        );
+    }
+
+    #[test]
+    fn block_sizes() {
+        // rounded up like GNU ls, the unit is only shown without a number
+        assert_eq!(format("M", 1), "1M");
+        assert_eq!(format("M", MB + 1), "2M");
+        assert_eq!(format("1K", 1500), "2");
+        assert_eq!(format("KB", 1500), "2KB");
+        assert_eq!(format("4KiB", 4096), "1");
+        assert_eq!(format("512", 0), "0");
+    }
+
+    #[test]
+    fn invalid_block_sizes() {
+        assert!(SizeStyle::parse("").is_err());
+        assert!(SizeStyle::parse("0").is_err());
+        assert!(SizeStyle::parse("0K").is_err());
+        assert!(SizeStyle::parse("X").is_err());
+        assert!(SizeStyle::parse("1.5M").is_err());
+        // overflow
+        assert!(SizeStyle::parse("99999999999999999999").is_err());
+        assert!(SizeStyle::parse("99999999T").is_err());
+    }
+
+    #[test]
+    fn config_round_trip() {
+        for style in ["short", "si", "binary", "bytes", "M", "KB", "4096"] {
+            assert_eq!(String::from(SizeStyle::parse(style).unwrap()), style);
+        }
+    }
+}
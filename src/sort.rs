@@ -0,0 +1,206 @@
+// sorting of directory entries
+use serde::{Deserialize, Serialize};
+
+use std::cmp::Ordering;
+
+use crate::{time::TimeField, FileData};
+
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortBy {
+    Name,
+    Size,
+    Time,
+    #[serde(rename = "ext")]
+    Extension,
+    Type,
+    None,
+}
+
+impl SortBy {
+    pub fn from_arg(arg: &str) -> SortBy {
+        match arg {
+            "size" => SortBy::Size,
+            "time" => SortBy::Time,
+            "ext" => SortBy::Extension,
+            "type" => SortBy::Type,
+            "none" => SortBy::None,
+            _ => SortBy::Name,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupDirs {
+    First,
+    Last,
+    None,
+}
+
+impl GroupDirs {
+    pub fn from_arg(arg: &str) -> GroupDirs {
+        match arg {
+            "first" => GroupDirs::First,
+            "last" => GroupDirs::Last,
+            _ => GroupDirs::None,
+        }
+    }
+}
+
+// 'time' sorts by the timestamp chosen with --time
+pub fn sort_entries(
+    entries: &mut [FileData],
+    sort_by: SortBy,
+    reverse: bool,
+    group: GroupDirs,
+    time: TimeField,
+) {
+    // sort_by is stable, so 'none' keeps the order of the filesystem
+    entries.sort_by(|a, b| {
+        let key = match sort_by {
+            SortBy::Name => natural_cmp(&a.name, &b.name),
+            // biggest and newest entries first, like ls does
+            SortBy::Size => b
+                .filesize
+                .cmp(&a.filesize)
+                .then_with(|| natural_cmp(&a.name, &b.name)),
+            SortBy::Time => b
+                .time(time)
+                .cmp(&a.time(time))
+                .then_with(|| natural_cmp(&a.name, &b.name)),
+            SortBy::Extension => natural_cmp(&a.file_extension, &b.file_extension)
+                .then_with(|| natural_cmp(&a.name, &b.name)),
+            SortBy::Type => type_rank(a)
+                .cmp(&type_rank(b))
+                .then_with(|| natural_cmp(&a.name, &b.name)),
+            SortBy::None => Ordering::Equal,
+        };
+        let key = if reverse { key.reverse() } else { key };
+
+        // grouping is not affected by reverse
+        group_rank(a, group).cmp(&group_rank(b, group)).then(key)
+    });
+
+    if sort_by == SortBy::None && reverse {
+        match group {
+            GroupDirs::None => entries.reverse(),
+            _ => {
+                let dirs = entries.iter().filter(|e| group_rank(e, group) == 0).count();
+                let (head, tail) = entries.split_at_mut(dirs);
+                head.reverse();
+                tail.reverse();
+            }
+        }
+    }
+}
+
+fn group_rank(entry: &FileData, group: GroupDirs) -> u8 {
+    let is_dir = entry.is_dir();
+    match group {
+        GroupDirs::First if !is_dir => 1,
+        GroupDirs::Last if is_dir => 1,
+        _ => 0,
+    }
+}
+
+fn type_rank(entry: &FileData) -> u8 {
+    if entry.is_dir() {
+        0
+    } else if entry.is_file() {
+        1
+    } else if entry.is_symlink() {
+        2
+    } else {
+        3
+    }
+}
+
+// compares names case-insensitive and numbers by their value, so 'file2' comes before 'file10'
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek(), b_chars.peek()) {
+            (None, None) => break,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x_num = take_number(&mut a_chars);
+                let y_num = take_number(&mut b_chars);
+                let x_trimmed = x_num.trim_start_matches('0');
+                let y_trimmed = y_num.trim_start_matches('0');
+
+                let ord = x_trimmed
+                    .len()
+                    .cmp(&y_trimmed.len())
+                    .then_with(|| x_trimmed.cmp(y_trimmed))
+                    .then_with(|| x_num.len().cmp(&y_num.len()));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ord = x.to_lowercase().cmp(y.to_lowercase());
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+
+    // fall back to a case-sensitive comparison for a stable order
+    a.cmp(b)
+}
+
+fn take_number(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut number = String::new();
+    while let Some(c) = chars.peek() {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        number.push(*c);
+        chars.next();
+    }
+    number
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_by_value() {
+        assert_eq!(natural_cmp("file2", "file10"), Ordering::Less);
+        assert_eq!(natural_cmp("file10", "file2"), Ordering::Greater);
+        assert_eq!(natural_cmp("a1b2", "a1b10"), Ordering::Less);
+        assert_eq!(natural_cmp("9", "10"), Ordering::Less);
+    }
+
+    #[test]
+    fn leading_zeros() {
+        // same value, fewer zeros first
+        assert_eq!(natural_cmp("file2", "file02"), Ordering::Less);
+        assert_eq!(natural_cmp("file002", "file10"), Ordering::Less);
+        assert_eq!(natural_cmp("0", "00"), Ordering::Less);
+    }
+
+    #[test]
+    fn long_numbers_dont_overflow() {
+        assert_eq!(
+            natural_cmp("x99999999999999999999999", "x100000000000000000000000"),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn case_insensitive() {
+        assert_eq!(natural_cmp("apple", "Banana"), Ordering::Less);
+        assert_eq!(natural_cmp("Apple", "apple"), Ordering::Less);
+        assert_eq!(natural_cmp("file", "file1"), Ordering::Less);
+        assert_eq!(natural_cmp("same", "same"), Ordering::Equal);
+    }
+}
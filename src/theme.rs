@@ -0,0 +1,280 @@
+// colour themes with named roles for every part of the output
+use log::warn;
+use owo_colors::{OwoColorize, Style as OwoStyle};
+use serde::{Deserialize, Serialize};
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::colour::{self, Depth};
+
+const THEME_DIR: &str = "themes";
+pub const DEFAULT_THEME: &str = "iceberg-dark";
+
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl TryFrom<String> for Rgb {
+    type Error = String;
+
+    // accepts hex colours like '#84a0c6'
+    fn try_from(hex: String) -> Result<Self, Self::Error> {
+        let digits = hex.trim_start_matches('#');
+        // only ascii digits can be sliced by bytes
+        if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!(
+                "invalid colour \'{hex}\', expected a hex value like \'#84a0c6\'"
+            ));
+        }
+
+        let channel = |i: usize| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .map_err(|err| format!("invalid colour \'{hex}\': {err}"))
+        };
+
+        Ok(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+impl From<Rgb> for String {
+    fn from(Rgb(r, g, b): Rgb) -> Self {
+        format!("#{r:02x}{g:02x}{b:02x}")
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Style {
+    pub fg: Option<Rgb>,
+    pub bg: Option<Rgb>,
+    pub bold: bool,
+    pub italic: bool,
+    pub dimmed: bool,
+}
+
+impl Style {
+    fn fg(rgb: Rgb) -> Style {
+        Style {
+            fg: Some(rgb),
+            ..Style::default()
+        }
+    }
+
+    fn on(self, rgb: Rgb) -> Style {
+        Style {
+            bg: Some(rgb),
+            ..self
+        }
+    }
+
+    fn bold(self) -> Style {
+        Style { bold: true, ..self }
+    }
+
+    fn italic(self) -> Style {
+        Style {
+            italic: true,
+            ..self
+        }
+    }
+
+    // colours are reduced to what the terminal supports
+    pub fn paint(&self, text: &str, depth: Depth) -> String {
+        let mut style = OwoStyle::new();
+        if let Some(rgb) = self.fg {
+            style = match depth {
+                Depth::TrueColor => style.truecolor(rgb.0, rgb.1, rgb.2),
+                Depth::Ansi256 => style.color(colour::to_ansi256(rgb)),
+                Depth::Ansi16 => style.color(colour::to_ansi16(rgb)),
+            };
+        }
+        if let Some(rgb) = self.bg {
+            style = match depth {
+                Depth::TrueColor => style.on_truecolor(rgb.0, rgb.1, rgb.2),
+                Depth::Ansi256 => style.on_color(colour::to_ansi256(rgb)),
+                Depth::Ansi16 => style.on_color(colour::to_ansi16(rgb)),
+            };
+        }
+        if self.bold {
+            style = style.bold();
+        }
+        if self.italic {
+            style = style.italic();
+        }
+        if self.dimmed {
+            style = style.dimmed();
+        }
+
+        text.style(style).to_string()
+    }
+}
+
+// a theme file only needs to contain the roles it changes,
+// every other role keeps the style of the default theme
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Theme {
+    pub file: Style,
+    pub dir: Style,
+    pub symlink: Style,
+    pub broken_link: Style,
+    pub exec: Style,
+    pub config: Style,
+    pub source: Style,
+    pub office: Style,
+    pub media: Style,
+    pub archive: Style,
+    pub other: Style,
+    pub notes_info: Style,
+    pub notes_important: Style,
+    pub notes_warn: Style,
+    pub size: Style,
+    pub unit: Style,
+    pub date: Style,
+    pub readonly: Style,
+    pub git_added: Style,
+    pub git_modified: Style,
+    pub git_deleted: Style,
+    pub git_untracked: Style,
+    pub git_ignored: Style,
+    pub git_conflicted: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        iceberg_dark()
+    }
+}
+
+// https://github.com/cocopon/iceberg.vim
+pub fn iceberg_dark() -> Theme {
+    let black = Rgb(30, 33, 50);
+    let white = Rgb(198, 200, 209);
+    let red = Rgb(226, 120, 120);
+    let green = Rgb(180, 190, 130);
+    let yellow = Rgb(226, 164, 120);
+    let blue = Rgb(109, 144, 217);
+    let pink = Rgb(173, 160, 211);
+    let cyan = Rgb(137, 184, 194);
+    let darkgray = Rgb(107, 112, 137);
+
+    Theme {
+        file: Style::fg(white),
+        dir: Style::fg(blue).bold(),
+        symlink: Style {
+            italic: true,
+            dimmed: true,
+            ..Style::default()
+        },
+        broken_link: Style::fg(red).italic(),
+        exec: Style::fg(red).bold(),
+        config: Style::fg(yellow),
+        source: Style::fg(green),
+        office: Style::fg(red),
+        media: Style::fg(pink),
+        archive: Style::fg(cyan),
+        other: Style::fg(darkgray),
+        notes_info: Style::fg(black).on(white),
+        notes_important: Style::fg(black).on(yellow),
+        notes_warn: Style::fg(black).on(red),
+        size: Style::fg(Rgb(102, 255, 179)),
+        unit: Style::fg(Rgb(50, 170, 130)),
+        date: Style::fg(Rgb(97, 88, 111)),
+        readonly: Style::fg(Rgb(250, 0, 104)),
+        git_added: Style::fg(green),
+        git_modified: Style::fg(yellow),
+        git_deleted: Style::fg(red),
+        git_untracked: Style::fg(cyan),
+        git_ignored: Style::fg(darkgray),
+        git_conflicted: Style::fg(red).bold(),
+    }
+}
+
+pub fn iceberg_light() -> Theme {
+    let background = Rgb(232, 233, 236);
+    let black = Rgb(51, 55, 76);
+    let red = Rgb(204, 81, 122);
+    let green = Rgb(102, 142, 61);
+    let yellow = Rgb(197, 115, 57);
+    let blue = Rgb(45, 83, 158);
+    let pink = Rgb(119, 89, 180);
+    let cyan = Rgb(63, 131, 166);
+    let gray = Rgb(131, 137, 163);
+
+    Theme {
+        file: Style::fg(black),
+        dir: Style::fg(blue).bold(),
+        symlink: Style {
+            italic: true,
+            dimmed: true,
+            ..Style::default()
+        },
+        broken_link: Style::fg(red).italic(),
+        exec: Style::fg(red).bold(),
+        config: Style::fg(yellow),
+        source: Style::fg(green),
+        office: Style::fg(red),
+        media: Style::fg(pink),
+        archive: Style::fg(cyan),
+        other: Style::fg(gray),
+        notes_info: Style::fg(background).on(gray),
+        notes_important: Style::fg(background).on(yellow),
+        notes_warn: Style::fg(background).on(red),
+        size: Style::fg(green),
+        unit: Style::fg(cyan),
+        date: Style::fg(pink),
+        readonly: Style::fg(red),
+        git_added: Style::fg(green),
+        git_modified: Style::fg(yellow),
+        git_deleted: Style::fg(red),
+        git_untracked: Style::fg(cyan),
+        git_ignored: Style::fg(gray),
+        git_conflicted: Style::fg(red).bold(),
+    }
+}
+
+// a theme is either one of the bundled themes, a path to a theme file
+// or the name of a theme file in <config_dir>/sl/themes
+pub fn load(name: &str, config_dir: &Path) -> Theme {
+    match name {
+        "iceberg-dark" => return iceberg_dark(),
+        "iceberg-light" => return iceberg_light(),
+        _ => {}
+    }
+
+    let mut path = PathBuf::from(name);
+    if !path.is_file() {
+        path = config_dir.join(THEME_DIR).join(format!("{name}.toml"));
+    }
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) => {
+            warn!("Unable to read the theme \'{}\': {}", name, err);
+            return Theme::default();
+        }
+    };
+
+    toml::from_str(&content).unwrap_or_else(|err| {
+        warn!("Invalid theme file \'{}\': {}", path.display(), err);
+        Theme::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colours() {
+        assert!(Rgb::try_from(String::from("#84a0c6")) == Ok(Rgb(132, 160, 198)));
+        assert!(Rgb::try_from(String::from("84A0C6")) == Ok(Rgb(132, 160, 198)));
+        assert!(Rgb::try_from(String::from("#84a0c")).is_err());
+        assert!(Rgb::try_from(String::from("#84a0cg")).is_err());
+        // six bytes, but not six characters
+        assert!(Rgb::try_from(String::from("#€€")).is_err());
+    }
+}
@@ -0,0 +1,242 @@
+// which timestamp is shown in the long listing and how it is formatted
+use chrono::{format::Item, format::StrftimeItems, DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use std::time::{Duration, SystemTime};
+
+// timestamps older than this show the year instead of the time with --time-style=iso
+const RECENT: Duration = Duration::from_secs(60 * 60 * 24 * 365 / 2);
+
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeField {
+    Modified,
+    Created,
+    Accessed,
+    // the last status change, only on unix
+    Changed,
+}
+
+impl TimeField {
+    pub fn from_arg(arg: &str) -> TimeField {
+        match arg {
+            "created" => TimeField::Created,
+            "accessed" => TimeField::Accessed,
+            "changed" => TimeField::Changed,
+            _ => TimeField::Modified,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum TimeStyle {
+    // '3 mins ago'
+    Relative,
+    // '3m'
+    Compact,
+    // 'MM-DD HH:MM' for recent timestamps and 'YYYY-MM-DD' otherwise, like GNU ls
+    Iso,
+    LongIso,
+    FullIso,
+    // a strftime format given as '+FORMAT'
+    Custom(String),
+}
+
+impl TimeStyle {
+    // used as clap value parser and for the config file
+    pub fn parse(arg: &str) -> Result<TimeStyle, String> {
+        match arg {
+            "relative" => Ok(TimeStyle::Relative),
+            "compact" => Ok(TimeStyle::Compact),
+            "iso" => Ok(TimeStyle::Iso),
+            "long-iso" => Ok(TimeStyle::LongIso),
+            "full-iso" => Ok(TimeStyle::FullIso),
+            _ => match arg.strip_prefix('+') {
+                // chrono panics on invalid formats when printing, so check them here
+                Some(format) if StrftimeItems::new(format).any(|item| item == Item::Error) => {
+                    Err(format!("invalid time format \'{format}\'"))
+                }
+                Some(format) => Ok(TimeStyle::Custom(format.to_string())),
+                None => Err(format!(
+                    "invalid time style \'{arg}\', expected relative, compact, iso, long-iso, full-iso or +FORMAT"
+                )),
+            },
+        }
+    }
+
+    // the minimum column width, so relative times keep their width while they change,
+    // the listing widens the column to its longest timestamp
+    pub fn width(&self) -> usize {
+        match self {
+            TimeStyle::Relative => 13,
+            TimeStyle::Compact => 7,
+            TimeStyle::Iso => 11,
+            TimeStyle::LongIso => 16,
+            TimeStyle::FullIso | TimeStyle::Custom(_) => 0,
+        }
+    }
+
+    pub fn format(&self, time: Option<SystemTime>) -> String {
+        let Some(time) = time else {
+            return String::from("?");
+        };
+
+        let local: DateTime<Local> = DateTime::from(time);
+        match self {
+            TimeStyle::Relative => relative(time, false),
+            TimeStyle::Compact => relative(time, true),
+            TimeStyle::Iso => {
+                let recent = SystemTime::now()
+                    .duration_since(time)
+                    .is_ok_and(|age| age < RECENT);
+                match recent {
+                    true => local.format("%m-%d %H:%M").to_string(),
+                    false => local.format("%Y-%m-%d").to_string(),
+                }
+            }
+            TimeStyle::LongIso => local.format("%Y-%m-%d %H:%M").to_string(),
+            TimeStyle::FullIso => local.format("%Y-%m-%d %H:%M:%S%.9f %z").to_string(),
+            TimeStyle::Custom(format) => local.format(format).to_string(),
+        }
+    }
+}
+
+impl TryFrom<String> for TimeStyle {
+    type Error = String;
+
+    fn try_from(style: String) -> Result<Self, Self::Error> {
+        TimeStyle::parse(&style)
+    }
+}
+
+impl From<TimeStyle> for String {
+    fn from(style: TimeStyle) -> Self {
+        match style {
+            TimeStyle::Relative => String::from("relative"),
+            TimeStyle::Compact => String::from("compact"),
+            TimeStyle::Iso => String::from("iso"),
+            TimeStyle::LongIso => String::from("long-iso"),
+            TimeStyle::FullIso => String::from("full-iso"),
+            TimeStyle::Custom(format) => format!("+{format}"),
+        }
+    }
+}
+
+// the units of the relative styles: seconds, verbose singular and plural, compact
+const UNITS: [(u64, &str, &str, &str); 7] = [
+    (1, "sec", "secs", "s"),
+    (MINUTE, "min", "mins", "m"),
+    (HOUR, "hr", "hrs", "h"),
+    (DAY, "day", "days", "d"),
+    (WEEK, "week", "weeks", "w"),
+    (MONTH, "month", "months", "mo"),
+    (YEAR, "year", "years", "y"),
+];
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+const MONTH: u64 = 30 * DAY;
+const YEAR: u64 = 365 * DAY;
+
+// '3 mins ago' or '3m', timestamps in the future are shown as 'in 3 mins' or 'in 3m'
+fn relative(time: SystemTime, compact: bool) -> String {
+    let (secs, future) = match SystemTime::now().duration_since(time) {
+        Ok(age) => (age.as_secs(), false),
+        Err(err) => (err.duration().as_secs(), true),
+    };
+
+    // the largest unit that fits, rounded down so '1 day ago' means at least a full day
+    let (unit, singular, plural, short) = match secs {
+        0..MINUTE => UNITS[0],
+        MINUTE..HOUR => UNITS[1],
+        HOUR..DAY => UNITS[2],
+        DAY..WEEK => UNITS[3],
+        WEEK..MONTH => UNITS[4],
+        MONTH..YEAR => UNITS[5],
+        YEAR.. => UNITS[6],
+    };
+    let count = secs / unit;
+
+    let amount = match compact {
+        true => format!("{count}{short}"),
+        false if count == 1 => format!("{count} {singular}"),
+        false => format!("{count} {plural}"),
+    };
+
+    match (future, compact) {
+        (true, _) => format!("in {amount}"),
+        (false, true) => amount,
+        (false, false) => format!("{amount} ago"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ago(secs: u64, compact: bool) -> String {
+        relative(SystemTime::now() - Duration::from_secs(secs), compact)
+    }
+
+    // a little extra, so the time passing during the test doesn't change the unit
+    fn ahead(secs: u64, compact: bool) -> String {
+        relative(
+            SystemTime::now() + Duration::from_secs(secs) + Duration::from_millis(500),
+            compact,
+        )
+    }
+
+    #[test]
+    fn verbose() {
+        assert_eq!(ago(0, false), "0 secs ago");
+        assert_eq!(ago(1, false), "1 sec ago");
+        assert_eq!(ago(59, false), "59 secs ago");
+        assert_eq!(ago(60, false), "1 min ago");
+        assert_eq!(ago(3 * HOUR, false), "3 hrs ago");
+        assert_eq!(ago(2 * WEEK, false), "2 weeks ago");
+        assert_eq!(ago(11 * MONTH, false), "11 months ago");
+        assert_eq!(ago(400 * DAY, false), "1 year ago");
+    }
+
+    #[test]
+    fn rounded_down() {
+        assert_eq!(ago(119, false), "1 min ago");
+        assert_eq!(ago(DAY - 1, false), "23 hrs ago");
+        assert_eq!(ago(WEEK - 1, false), "6 days ago");
+        assert_eq!(ago(MONTH - 1, false), "4 weeks ago");
+        assert_eq!(ago(YEAR - 1, false), "12 months ago");
+    }
+
+    #[test]
+    fn compact() {
+        assert_eq!(ago(5, true), "5s");
+        assert_eq!(ago(5 * MINUTE, true), "5m");
+        assert_eq!(ago(5 * DAY, true), "5d");
+        assert_eq!(ago(5 * MONTH, true), "5mo");
+        assert_eq!(ago(5 * YEAR, true), "5y");
+    }
+
+    #[test]
+    fn future() {
+        assert_eq!(ahead(3 * MINUTE, false), "in 3 mins");
+        assert_eq!(ahead(DAY, false), "in 1 day");
+        assert_eq!(ahead(2 * HOUR, true), "in 2h");
+    }
+
+    #[test]
+    fn styles() {
+        assert!(TimeStyle::parse("+%Y-%m-%d").is_ok());
+        assert!(TimeStyle::parse("+%Q").is_err());
+        assert!(TimeStyle::parse("short").is_err());
+        assert_eq!(TimeStyle::Iso.format(None), "?");
+        assert_eq!(
+            TimeStyle::Custom(String::from("%Y")).format(Some(
+                SystemTime::UNIX_EPOCH + Duration::from_secs(YEAR * 10)
+            )),
+            "1979"
+        );
+    }
+}